@@ -0,0 +1,157 @@
+/*
+    This file is a part of term-string.
+
+    Copyright (C) 2018 Mohammad AlSaleh <CE.Mohammad.AlSaleh at gmail.com>
+    https://github.com/rust-alt/term-string
+
+    This Source Code Form is subject to the terms of the Mozilla Public
+    License, v. 2.0. If a copy of the MPL was not distributed with this
+    file, You can obtain one at <http://mozilla.org/MPL/2.0/>.
+*/
+
+#[cfg(test)]
+mod tests;
+
+use std::env;
+use std::ffi::OsStr;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use error::{Error, Result};
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+/// Whether to write styled output or not.
+///
+/// A process-wide choice can be set with [`set_global()`], which is used by
+/// [`TermString::print()`] and friends. The `*_with()` print methods take
+/// a choice per call instead.
+///
+/// [`set_global()`]: ColorChoice::set_global
+/// [`TermString::print()`]: ::TermString::print
+pub enum ColorChoice {
+    /// Always write styled output. ANSI escape sequences are written if
+    /// terminfo for the terminal can't be loaded.
+    Always,
+    /// Decide based on the environment, and whether the output is a tty.
+    /// Check out [`should_style()`] for details.
+    ///
+    /// [`should_style()`]: ColorChoice::should_style
+    Auto,
+    /// Never write styled output.
+    Never,
+}
+
+static GLOBAL_CHOICE: AtomicUsize = AtomicUsize::new(1);
+
+// Whether ColorChoice::Auto resolves to styled output, given the values
+// of the environment variables checked.
+fn resolve(
+    clicolor_force: Option<&OsStr>,
+    no_color: Option<&OsStr>,
+    clicolor: Option<&OsStr>,
+    term: Option<&OsStr>,
+    is_tty: bool,
+) -> bool {
+    let is = |var: Option<&OsStr>, val: &str| var.map_or(false, |var| var == val);
+    let non_empty = |var: Option<&OsStr>| var.map_or(false, |var| !var.is_empty());
+
+    if non_empty(clicolor_force) && !is(clicolor_force, "0") {
+        true
+    } else if non_empty(no_color) || is(clicolor, "0") || is(term, "dumb") {
+        false
+    } else {
+        is_tty
+    }
+}
+
+impl ColorChoice {
+    /// Get the process-wide choice. [`ColorChoice::Auto`] is the default.
+    pub fn global() -> Self {
+        match GLOBAL_CHOICE.load(Ordering::Relaxed) {
+            0 => ColorChoice::Always,
+            2 => ColorChoice::Never,
+            _ => ColorChoice::Auto,
+        }
+    }
+
+    /// Set the process-wide choice.
+    ///
+    /// # Examples
+    /// ``` rust
+    /// use term_string::ColorChoice;
+    ///
+    /// // e.g. --color=always
+    /// ColorChoice::set_global(ColorChoice::Always);
+    /// assert_eq!(ColorChoice::global(), ColorChoice::Always);
+    /// ```
+    pub fn set_global(choice: Self) {
+        let val = match choice {
+            ColorChoice::Always => 0,
+            ColorChoice::Auto => 1,
+            ColorChoice::Never => 2,
+        };
+        GLOBAL_CHOICE.store(val, Ordering::Relaxed);
+    }
+
+    /// Check if output should be styled, `is_tty` being whether
+    /// the output is a tty.
+    ///
+    /// [`ColorChoice::Auto`] is resolved in this order:
+    ///
+    /// * `CLICOLOR_FORCE` set (and not `0`): styled.
+    /// * `NO_COLOR` set (and not empty): not styled.
+    /// * `CLICOLOR` set to `0`: not styled.
+    /// * `TERM` set to `dumb`: not styled.
+    /// * Otherwise, styled only if `is_tty` is `true`.
+    ///
+    /// # Examples
+    /// ``` rust
+    /// use term_string::ColorChoice;
+    ///
+    /// assert!(ColorChoice::Always.should_style(false));
+    /// assert!(!ColorChoice::Never.should_style(true));
+    /// ```
+    pub fn should_style(self, is_tty: bool) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => resolve(
+                env::var_os("CLICOLOR_FORCE").as_deref(),
+                env::var_os("NO_COLOR").as_deref(),
+                env::var_os("CLICOLOR").as_deref(),
+                env::var_os("TERM").as_deref(),
+                is_tty,
+            ),
+        }
+    }
+}
+
+/// [`ColorChoice::Auto`].
+impl Default for ColorChoice {
+    fn default() -> Self {
+        ColorChoice::Auto
+    }
+}
+
+/// Parse a [`ColorChoice`] from `always`, `auto`, or `never`, as
+/// commonly passed to `--color`.
+///
+/// # Examples
+/// ``` rust
+/// use term_string::ColorChoice;
+///
+/// assert_eq!("always".parse::<ColorChoice>().unwrap(), ColorChoice::Always);
+/// assert!("sometimes".parse::<ColorChoice>().is_err());
+/// ```
+impl FromStr for ColorChoice {
+    type Err = Error;
+
+    fn from_str(choice: &str) -> Result<Self> {
+        match choice {
+            "always" => Ok(ColorChoice::Always),
+            "auto" => Ok(ColorChoice::Auto),
+            "never" => Ok(ColorChoice::Never),
+            _ => Err(Error::Parse(format!("unknown color choice `{}`", choice))),
+        }
+    }
+}
@@ -0,0 +1,91 @@
+/*
+    This file is a part of term-string.
+
+    Copyright (C) 2018 Mohammad AlSaleh <CE.Mohammad.AlSaleh at gmail.com>
+    https://github.com/rust-alt/term-string
+
+    This Source Code Form is subject to the terms of the Mozilla Public
+    License, v. 2.0. If a copy of the MPL was not distributed with this
+    file, You can obtain one at <http://mozilla.org/MPL/2.0/>.
+*/
+
+use std::ffi::OsStr;
+
+use super::{resolve, ColorChoice};
+
+type Var<'a> = Option<&'a str>;
+
+// resolve() with str values
+fn auto(force: Var, no_color: Var, clicolor: Var, term: Var, is_tty: bool) -> bool {
+    fn os(var: Option<&str>) -> Option<&OsStr> {
+        var.map(OsStr::new)
+    }
+    resolve(os(force), os(no_color), os(clicolor), os(term), is_tty)
+}
+
+#[test]
+fn resolve_tty() {
+    // Nothing set
+    assert!(auto(None, None, None, None, true));
+    assert!(!auto(None, None, None, None, false));
+
+    // Irrelevant values
+    assert!(auto(None, None, Some("1"), Some("xterm"), true));
+    assert!(!auto(None, None, Some("1"), Some("xterm"), false));
+    assert!(auto(Some(""), Some(""), Some(""), Some(""), true));
+    assert!(!auto(Some(""), Some(""), Some(""), Some(""), false));
+}
+
+#[test]
+fn resolve_off() {
+    for &is_tty in &[true, false] {
+        // NO_COLOR set to anything non-empty
+        assert!(!auto(None, Some("1"), None, None, is_tty));
+        assert!(!auto(None, Some("0"), None, None, is_tty));
+        assert!(!auto(None, Some("1"), Some("1"), Some("xterm"), is_tty));
+
+        // CLICOLOR=0
+        assert!(!auto(None, None, Some("0"), None, is_tty));
+
+        // TERM=dumb
+        assert!(!auto(None, None, None, Some("dumb"), is_tty));
+    }
+}
+
+#[test]
+fn resolve_force() {
+    for &is_tty in &[true, false] {
+        // CLICOLOR_FORCE wins over everything
+        assert!(auto(Some("1"), None, None, None, is_tty));
+        assert!(auto(Some("yes"), None, None, None, is_tty));
+        assert!(auto(Some("1"), Some("1"), None, None, is_tty));
+        assert!(auto(Some("1"), None, Some("0"), None, is_tty));
+        assert!(auto(Some("1"), None, None, Some("dumb"), is_tty));
+        assert!(auto(Some("1"), Some("1"), Some("0"), Some("dumb"), is_tty));
+
+        // Unless it's 0 or empty
+        for &force in &["0", ""] {
+            assert_eq!(auto(Some(force), None, None, None, is_tty), is_tty);
+            assert!(!auto(Some(force), Some("1"), None, None, is_tty));
+            assert!(!auto(Some(force), None, Some("0"), None, is_tty));
+            assert!(!auto(Some(force), None, None, Some("dumb"), is_tty));
+        }
+    }
+}
+
+#[test]
+fn should_style() {
+    for &is_tty in &[true, false] {
+        assert!(ColorChoice::Always.should_style(is_tty));
+        assert!(!ColorChoice::Never.should_style(is_tty));
+    }
+}
+
+#[test]
+fn from_str() {
+    assert_eq!("always".parse::<ColorChoice>().unwrap(), ColorChoice::Always);
+    assert_eq!("auto".parse::<ColorChoice>().unwrap(), ColorChoice::Auto);
+    assert_eq!("never".parse::<ColorChoice>().unwrap(), ColorChoice::Never);
+    assert!("Always".parse::<ColorChoice>().is_err());
+    assert!("".parse::<ColorChoice>().is_err());
+}
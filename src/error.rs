@@ -19,6 +19,7 @@ pub type Result<T> = result::Result<T, Error>;
 pub enum Error {
     StdIO(io::Error),
     Term(term::Error),
+    Parse(String),
     Other(String),
 }
 
@@ -27,6 +28,7 @@ impl fmt::Display for Error {
         match *self {
             Error::StdIO(ref e) => write!(f, "IO Error: {}", e),
             Error::Term(ref e) => write!(f, "Term Error: {}", e),
+            Error::Parse(ref e) => write!(f, "Parse Error: {}", e),
             Error::Other(ref e) => write!(f, "Error: {}", e),
         }
     }
@@ -50,15 +50,30 @@ extern crate mashup;
 pub extern crate isatty;
 pub extern crate term;
 
+#[cfg(feature = "regex")]
+pub extern crate regex;
+
+extern crate unicode_segmentation;
+extern crate unicode_width;
+
 #[macro_use]
 mod common_macros;
 
+mod choice;
 mod error;
 mod string;
 mod style;
 
 #[doc(inline)]
-pub use style::{color, Attr, TermStyle};
+pub use choice::ColorChoice;
+
+#[doc(inline)]
+pub use error::{Error, Result};
+
+#[doc(inline)]
+pub use style::{color, Attr, Attrs, ColorDepth, Rgb, TermStyle};
 
 #[doc(inline)]
-pub use string::{TermString, TermWrite};
+pub use string::{
+    ControlPolicy, MergeMode, Replacement, SanitizeMode, Sanitizer, TermString, TermWrite,
+};
@@ -0,0 +1,237 @@
+/*
+    This file is a part of term-string.
+
+    Copyright (C) 2018 Mohammad AlSaleh <CE.Mohammad.AlSaleh at gmail.com>
+    https://github.com/rust-alt/term-string
+
+    This Source Code Form is subject to the terms of the Mozilla Public
+    License, v. 2.0. If a copy of the MPL was not distributed with this
+    file, You can obtain one at <http://mozilla.org/MPL/2.0/>.
+*/
+
+use style::TermStyle;
+
+use super::{TermString, TermWrite};
+
+pub(crate) const ESC: char = '\x1b';
+const BEL: char = '\x07';
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+/// What to do with escape sequences other than SGR ones, when
+/// parsing ANSI-escaped text with [`TermString::from_ansi_with()`].
+///
+/// [`TermString::from_ansi_with()`]: TermString::from_ansi_with
+pub enum ControlPolicy {
+    /// Remove the sequences from the parsed text.
+    Drop,
+    /// Keep the sequences as-is in the parsed text.
+    Preserve,
+}
+
+// A parsed piece of ANSI-escaped text.
+pub(crate) enum Token<'a> {
+    Text(&'a str),
+    Sgr(&'a str),
+    Control(&'a str),
+}
+
+// Split ANSI-escaped text into text, SGR params, and other control sequences.
+pub(crate) struct Tokens<'a> {
+    pub(crate) text: &'a str,
+}
+
+impl<'a> Tokens<'a> {
+    // Length of the sequence starting with ESC at the start of text.
+    // Incomplete sequences span the rest of text.
+    fn seq_len(text: &str) -> usize {
+        let mut chars = text.char_indices().skip(1);
+
+        match chars.next() {
+            // CSI: parameter and intermediate bytes, then a final byte
+            Some((_, '[')) => chars
+                .find(|&(_, c)| ('\x40'..='\x7e').contains(&c))
+                .map_or(text.len(), |(i, c)| i + c.len_utf8()),
+            // OSC, DCS, SOS, PM and APC: terminated by ST (ESC \) or BEL
+            Some((_, ']')) | Some((_, 'P')) | Some((_, 'X')) | Some((_, '^')) | Some((_, '_')) => {
+                let mut prev = ' ';
+                chars
+                    .find(|&(_, c)| {
+                        let end = c == BEL || (prev == ESC && c == '\\');
+                        prev = c;
+                        end
+                    })
+                    .map_or(text.len(), |(i, _)| i + 1)
+            },
+            // Other escapes: intermediate bytes, then a final byte
+            Some(_) => text
+                .char_indices()
+                .skip(1)
+                .find(|&(_, c)| !(' '..='/').contains(&c))
+                .map_or(text.len(), |(i, c)| i + c.len_utf8()),
+            None => text.len(),
+        }
+    }
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Token<'a>> {
+        if self.text.is_empty() {
+            return None;
+        }
+
+        let len = match self.text.find(ESC) {
+            Some(0) => Self::seq_len(self.text),
+            Some(pos) => pos,
+            None => self.text.len(),
+        };
+
+        let (curr, rest) = self.text.split_at(len);
+        self.text = rest;
+
+        if !curr.starts_with(ESC) {
+            return Some(Token::Text(curr));
+        }
+
+        // Private CSI sequences (e.g. ESC [ ? 25 l) are not SGR,
+        // even with 'm' as their final byte.
+        let is_sgr = curr.len() > 2
+            && curr.starts_with("\x1b[")
+            && curr.ends_with('m')
+            && curr[2..curr.len() - 1]
+                .bytes()
+                .all(|b| b.is_ascii_digit() || b == b';');
+
+        if is_sgr {
+            Some(Token::Sgr(&curr[2..curr.len() - 1]))
+        } else {
+            Some(Token::Control(curr))
+        }
+    }
+}
+
+/// Methods for parsing ANSI-escaped text, like the output of
+/// compilers, `git`, or `ls`.
+impl TermString {
+    /// Parse ANSI-escaped text into a [`TermString`], dropping escape
+    /// sequences other than SGR ones.
+    ///
+    /// This is equivalent to `TermString::from_ansi_with(text, ControlPolicy::Drop)`.
+    ///
+    /// # Examples
+    /// ``` rust
+    /// use term_string::TermString;
+    ///
+    /// // "error" is bold and red, the rest is un-styled
+    /// let ts = TermString::from_ansi("\x1b[1;31merror\x1b[0m: \x1b[2Kfailed");
+    /// assert_eq!(ts.as_string(), "error: failed");
+    /// ts.println();
+    /// ```
+    pub fn from_ansi(text: &str) -> Self {
+        Self::from_ansi_with(text, ControlPolicy::Drop)
+    }
+
+    /// Parse ANSI-escaped text into a [`TermString`].
+    ///
+    /// SGR sequences (`ESC [ ... m`) are applied to the style of the text
+    /// following them, as a terminal would. Unknown SGR codes are ignored.
+    /// Other escape sequences (cursor movement, OSC titles and links, ...)
+    /// are handled according to `policy`. With [`ControlPolicy::Preserve`],
+    /// they are kept in the text with the style active at their position.
+    ///
+    /// # Examples
+    /// ``` rust
+    /// use term_string::{ControlPolicy, TermString};
+    ///
+    /// let text = "\x1b]0;title\x07\x1b[4mlink\x1b[m";
+    /// let ts = TermString::from_ansi_with(text, ControlPolicy::Preserve);
+    /// assert_eq!(ts.as_string(), "\x1b]0;title\x07link");
+    /// ```
+    pub fn from_ansi_with(text: &str, policy: ControlPolicy) -> Self {
+        let mut style = TermStyle::default();
+        let mut ts = Self::default();
+
+        for token in (Tokens { text }) {
+            match (token, policy) {
+                (Token::Text(text), _) | (Token::Control(text), ControlPolicy::Preserve) => {
+                    ts.append_term_str(Self::new(style, text))
+                },
+                (Token::Sgr(params), _) => {
+                    let _ = style.apply_sgr(params, false);
+                },
+                (Token::Control(_), ControlPolicy::Drop) => (),
+            }
+        }
+
+        ts
+    }
+}
+
+/// Methods for rendering a [`TermString`] with ANSI (ECMA-48) SGR escape
+/// sequences directly, without going through terminfo.
+///
+/// Unlike [`write_styled()`], the output doesn't depend on `TERM` or the
+/// terminal's capabilities. This is useful for producing colored logs
+/// (e.g. in CI), or for comparing exact output bytes in tests.
+///
+/// [`Rgb`] colors are written as-is.
+///
+/// Between adjacent text with different styles, only the attributes that
+/// change are written (or a reset, if that's shorter). A reset is written
+/// at the end, if any styling is active.
+///
+/// [`write_styled()`]: TermString::write_styled
+/// [`Rgb`]: ::Rgb
+impl TermString {
+    /// Write [`TermString`] to `out` with ANSI escape sequences.
+    ///
+    /// # Examples
+    /// ``` rust
+    /// # use term_string::{TermString, TermStyle};
+    /// let bold = TermStyle::bold();
+    /// let ts = TermString::new(bold, "some bold text");
+    ///
+    /// // This will write "some bold text" to stdout as bold
+    /// // text, regardless of TERM.
+    /// ts.write_ansi(std::io::stdout());
+    /// ```
+    pub fn write_ansi<W: TermWrite>(&self, out: W) {
+        let _ = self.write_ansi_ret_out(out);
+    }
+
+    /// Same as [`write_ansi()`], but returns `out` back
+    ///
+    /// [`write_ansi()`]: TermString::write_ansi
+    pub fn write_ansi_ret_out<W: TermWrite>(&self, mut out: W) -> W {
+        let mut prev = TermStyle::default();
+
+        for e in self.elements.iter().filter(|e| !e.text.is_empty()) {
+            let _ = match e.style._sgr_from(&prev) {
+                ref sgr if sgr.is_empty() => write!(out, "{}", e.text),
+                sgr => write!(out, "\x1b[{}m{}", sgr, e.text),
+            };
+            prev = e.style;
+        }
+
+        if !TermStyle::default()._sgr_from(&prev).is_empty() {
+            let _ = write!(out, "\x1b[0m");
+        }
+
+        out
+    }
+
+    /// Return [`TermString`] rendered with ANSI escape sequences.
+    ///
+    /// # Examples
+    /// ``` rust
+    /// use term_string::{color, TermString, TermStyle};
+    ///
+    /// let ts = TermString::new(TermStyle::fg(color::RED), "red") + TermString::from(" plain");
+    /// assert_eq!(ts.to_ansi_string(), "\x1b[31mred\x1b[0m plain");
+    /// ```
+    pub fn to_ansi_string(&self) -> String {
+        let out = self.write_ansi_ret_out(Vec::with_capacity(self.len() + 64));
+        String::from_utf8(out).expect("should never happen")
+    }
+}
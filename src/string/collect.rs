@@ -0,0 +1,180 @@
+/*
+    This file is a part of term-string.
+
+    Copyright (C) 2018 Mohammad AlSaleh <CE.Mohammad.AlSaleh at gmail.com>
+    https://github.com/rust-alt/term-string
+
+    This Source Code Form is subject to the terms of the Mozilla Public
+    License, v. 2.0. If a copy of the MPL was not distributed with this
+    file, You can obtain one at <http://mozilla.org/MPL/2.0/>.
+*/
+
+use std::iter::{FromIterator, Sum};
+
+use super::TermString;
+
+/// Methods for building [`TermString`]s from multiple values.
+impl TermString {
+    /// Join values converted into [`TermString`]s, with `sep` between
+    /// each two of them.
+    ///
+    /// The separator keeps its own styles. Adjacent text with equal styles
+    /// is merged, as with [`append_term_str()`].
+    ///
+    /// [`append_term_str()`]: TermString::append_term_str
+    ///
+    /// # Examples
+    ///
+    /// ``` rust
+    /// use term_string::{color, TermString, TermStyle};
+    ///
+    /// let files = vec!["a.rs", "b.rs", "c.rs"];
+    /// let sep = TermString::new(TermStyle::fg(color::BLUE), ", ");
+    /// let ts = TermString::join(files, sep);
+    /// assert_eq!(ts.as_string(), "a.rs, b.rs, c.rs");
+    /// ```
+    pub fn join<I, IS1, IS2>(iter: I, sep: IS2) -> Self
+    where
+        I: IntoIterator<Item = IS1>,
+        IS1: Into<Self>,
+        IS2: Into<Self>,
+    {
+        let sep = sep.into();
+        let mut ts = Self::default();
+
+        for (i, other) in iter.into_iter().enumerate() {
+            if i > 0 {
+                ts.append_term_str(sep.clone());
+            }
+            ts.append_term_str(other);
+        }
+
+        ts
+    }
+}
+
+/// Collect [`TermString`]s into one, as with [`append_term_str()`].
+///
+/// [`append_term_str()`]: TermString::append_term_str
+///
+/// # Examples
+///
+/// ``` rust
+/// use term_string::{TermString, TermStyle};
+///
+/// let ts: TermString = (1..4).map(|n| TermString::new(TermStyle::bold(), n.to_string())).collect();
+/// assert_eq!(ts.to_ansi_string(), "\x1b[1m123\x1b[0m");
+/// ```
+impl FromIterator<TermString> for TermString {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = TermString>,
+    {
+        let mut ts = Self::default();
+        ts.extend(iter);
+        ts
+    }
+}
+
+/// Collect string values into a [`TermString`], as with [`append_str()`].
+///
+/// [`append_str()`]: TermString::append_str
+impl<'a> FromIterator<&'a str> for TermString {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let mut ts = Self::default();
+        ts.extend(iter);
+        ts
+    }
+}
+
+/// Collect string values into a [`TermString`], as with [`append_str()`].
+///
+/// [`append_str()`]: TermString::append_str
+impl FromIterator<String> for TermString {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut ts = Self::default();
+        ts.extend(iter);
+        ts
+    }
+}
+
+/// Extend [`TermString`] with [`TermString`]s, as with [`append_term_str()`].
+///
+/// [`append_term_str()`]: TermString::append_term_str
+impl Extend<TermString> for TermString {
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = TermString>,
+    {
+        for other in iter {
+            self.append_term_str(other);
+        }
+    }
+}
+
+/// Extend [`TermString`] with string values, as with [`append_str()`].
+/// So, like `+=`, appended text inherits the previous style.
+///
+/// [`append_str()`]: TermString::append_str
+///
+/// # Examples
+///
+/// ``` rust
+/// use term_string::{TermString, TermStyle};
+///
+/// let mut ts = TermString::new(TermStyle::bold(), "a");
+/// ts.extend(vec!["b", "c"]);
+/// assert_eq!(ts.to_ansi_string(), "\x1b[1mabc\x1b[0m");
+/// ```
+impl<'a> Extend<&'a str> for TermString {
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        for text in iter {
+            self.append_str(text);
+        }
+    }
+}
+
+/// Extend [`TermString`] with string values, as with [`append_str()`].
+///
+/// [`append_str()`]: TermString::append_str
+impl Extend<String> for TermString {
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = String>,
+    {
+        for text in iter {
+            self.append_str(text);
+        }
+    }
+}
+
+/// Sum [`TermString`]s, as with [`append_term_str()`].
+///
+/// [`append_term_str()`]: TermString::append_term_str
+///
+/// # Examples
+///
+/// ``` rust
+/// use term_string::{TermString, TermStyle};
+///
+/// let parts = vec![TermString::new(TermStyle::bold(), "a"), TermString::from("b")];
+/// let ts = parts.into_iter().sum::<TermString>();
+/// assert_eq!(ts.as_string(), "ab");
+/// ```
+impl Sum for TermString {
+    fn sum<I>(iter: I) -> Self
+    where
+        I: Iterator<Item = TermString>,
+    {
+        iter.collect()
+    }
+}
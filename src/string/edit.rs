@@ -0,0 +1,228 @@
+/*
+    This file is a part of term-string.
+
+    Copyright (C) 2018 Mohammad AlSaleh <CE.Mohammad.AlSaleh at gmail.com>
+    https://github.com/rust-alt/term-string
+
+    This Source Code Form is subject to the terms of the Mozilla Public
+    License, v. 2.0. If a copy of the MPL was not distributed with this
+    file, You can obtain one at <http://mozilla.org/MPL/2.0/>.
+*/
+
+use std::borrow::Borrow;
+use std::ops::RangeBounds;
+
+use super::TermString;
+
+/// Methods for editing [`TermString`] in place, other than appending.
+///
+/// Offsets are byte offsets into the un-styled string, like with [`String`].
+/// Adjacent text with equal styles is merged after each edit.
+impl TermString {
+    /// Insert a [`TermString`] at byte offset `idx`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is out of bounds, or not on a char boundary.
+    ///
+    /// # Examples
+    ///
+    /// ``` rust
+    /// # use term_string::{TermString, TermStyle};
+    /// let mut ts = TermString::from("hello world");
+    /// ts.insert(6, TermString::new(TermStyle::bold(), "big "));
+    /// assert_eq!(ts.to_ansi_string(), "hello \x1b[1mbig \x1b[0mworld");
+    /// ```
+    pub fn insert<IS>(&mut self, idx: usize, other: IS)
+    where
+        IS: Into<Self>,
+    {
+        self._checked_range(&(idx..idx));
+
+        let pos = self._split_element_at(idx);
+        let other = other.into();
+        self.elements.splice(pos..pos, other.elements);
+        self._normalize();
+    }
+
+    /// Insert a string value at byte offset `idx`, inheriting the style
+    /// of the text before `idx`, as [`append_str()`] does at the end.
+    /// So at a boundary between two styles, the preceding one is used.
+    /// Text inserted at the start inherits the style of the first text.
+    ///
+    /// [`append_str()`]: TermString::append_str
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is out of bounds, or not on a char boundary.
+    ///
+    /// # Examples
+    ///
+    /// ``` rust
+    /// # use term_string::{TermString, TermStyle};
+    /// let mut ts = TermString::new(TermStyle::bold(), "hello") + TermString::from(" world");
+    /// ts.insert_str(5, "!!");
+    /// assert_eq!(ts.to_ansi_string(), "\x1b[1mhello!!\x1b[0m world");
+    /// ```
+    pub fn insert_str<S>(&mut self, idx: usize, text: S)
+    where
+        S: Borrow<str>,
+    {
+        self._checked_range(&(idx..idx));
+
+        let style = match idx {
+            0 => self._style_at(0),
+            _ if idx == self.len() => self._last_style(),
+            _ => self._style_at(idx - 1),
+        };
+        self.insert(idx, Self::new(style, text));
+    }
+
+    /// Insert a [`TermString`] at the start of [`TermString`].
+    ///
+    /// # Examples
+    ///
+    /// ``` rust
+    /// # use term_string::{color, TermString, TermStyle};
+    /// let mut ts = TermString::from("message");
+    /// ts.prepend(TermString::new(TermStyle::fg(color::BLUE), "> "));
+    /// assert_eq!(ts.as_string(), "> message");
+    /// ```
+    pub fn prepend<IS>(&mut self, other: IS)
+    where
+        IS: Into<Self>,
+    {
+        self.insert(0, other);
+    }
+
+    /// Remove the text in a byte range of [`TermString`], and return it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds, or not on char boundaries.
+    ///
+    /// # Examples
+    ///
+    /// ``` rust
+    /// # use term_string::{TermString, TermStyle};
+    /// let mut ts = TermString::new(TermStyle::bold(), "some bold") + TermString::from(" text");
+    /// let removed = ts.remove(4..9);
+    /// assert_eq!(ts.as_string(), "some text");
+    /// assert_eq!(removed.as_string(), " bold");
+    /// ```
+    pub fn remove<R>(&mut self, range: R) -> Self
+    where
+        R: RangeBounds<usize>,
+    {
+        let (start, end) = self._checked_range(&range);
+
+        let start_pos = self._split_element_at(start);
+        let end_pos = self._split_element_at(end);
+        let mut removed = Self {
+            elements: self.elements.drain(start_pos..end_pos).collect(),
+        };
+
+        self._normalize();
+        removed._normalize();
+        removed
+    }
+
+    /// Shorten [`TermString`] to `len` bytes.
+    ///
+    /// Nothing happens if `len` is greater than or equal to the length of
+    /// [`TermString`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` is not on a char boundary.
+    ///
+    /// # Examples
+    ///
+    /// ``` rust
+    /// # use term_string::{TermString, TermStyle};
+    /// let mut ts = TermString::new(TermStyle::bold(), "bold") + TermString::from(" text");
+    /// ts.truncate(4);
+    /// assert_eq!(ts.as_string(), "bold");
+    /// ```
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len() {
+            self.remove(len..);
+        }
+    }
+
+    /// Remove all text from [`TermString`].
+    ///
+    /// # Examples
+    ///
+    /// ``` rust
+    /// # use term_string::{TermString, TermStyle};
+    /// let mut ts = TermString::new(TermStyle::bold(), "bold");
+    /// ts.clear();
+    /// assert!(ts.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        self.elements.clear();
+    }
+
+    /// Remove the last char from [`TermString`], and return it.
+    ///
+    /// `None` is returned if [`TermString`] is empty.
+    ///
+    /// # Examples
+    ///
+    /// ``` rust
+    /// # use term_string::{TermString, TermStyle};
+    /// let mut ts = TermString::new(TermStyle::bold(), "ab") + TermString::from("é");
+    /// assert_eq!(ts.pop(), Some('é'));
+    /// assert_eq!(ts.pop(), Some('b'));
+    /// assert_eq!(ts.as_string(), "a");
+    /// ```
+    pub fn pop(&mut self) -> Option<char> {
+        let c = self
+            .elements
+            .iter_mut()
+            .rev()
+            .find(|e| !e.text.is_empty())
+            .and_then(|e| e.text.pop());
+
+        self._normalize();
+        c
+    }
+
+    chaining_fn!(TermString, insert,
+                 pub fn with_inserted<IS>(mut self, idx: usize, other: IS) -> Self
+                 where
+                     IS: Into<Self>,
+                 {
+                     self.insert(idx, other);
+                     self
+                 }
+    );
+
+    chaining_fn!(TermString, insert_str,
+                 pub fn with_inserted_str<S>(mut self, idx: usize, text: S) -> Self
+                 where
+                     S: Borrow<str>,
+                 {
+                     self.insert_str(idx, text);
+                     self
+                 }
+    );
+
+    chaining_fn!(TermString, prepend,
+                 pub fn with_prepended<IS>(mut self, other: IS) -> Self
+                 where
+                     IS: Into<Self>,
+                 {
+                     self.prepend(other);
+                     self
+                 }
+    );
+
+    chaining_fn!(TermString, truncate,
+                 pub fn with_truncated(mut self, len: usize) -> Self {
+                     self.truncate(len);
+                     self
+                 }
+    );
+}
@@ -0,0 +1,177 @@
+/*
+    This file is a part of term-string.
+
+    Copyright (C) 2018 Mohammad AlSaleh <CE.Mohammad.AlSaleh at gmail.com>
+    https://github.com/rust-alt/term-string
+
+    This Source Code Form is subject to the terms of the Mozilla Public
+    License, v. 2.0. If a copy of the MPL was not distributed with this
+    file, You can obtain one at <http://mozilla.org/MPL/2.0/>.
+*/
+
+#[cfg(feature = "regex")]
+use regex::Regex;
+
+use style::TermStyle;
+
+use super::{MergeMode, TermString};
+
+/// Methods for highlighting matches in a [`TermString`].
+///
+/// The highlight style is added to the styles of matched text, overlaying
+/// them (check out [`MergeMode::Add`]). Matches are found in the un-styled
+/// string, so they may span multiple styles. Text outside matches keeps
+/// its style.
+///
+/// [`MergeMode::Add`]: ::MergeMode::Add
+impl TermString {
+    /// Highlight all matches of `pat` with `style`.
+    ///
+    /// # Examples
+    ///
+    /// ``` rust
+    /// use term_string::{color, TermString, TermStyle};
+    ///
+    /// let mut ts = TermString::new(TermStyle::fg(color::BLUE), "foo") + TermString::from("bar foo");
+    /// ts.highlight("ob", TermStyle::bold());
+    /// assert_eq!(ts.to_ansi_string(), "\x1b[34mfo\x1b[1mo\x1b[39mb\x1b[0mar foo");
+    /// ```
+    pub fn highlight<IT>(&mut self, pat: &str, style: IT)
+    where
+        IT: Into<TermStyle>,
+    {
+        let style = style.into();
+
+        if pat.is_empty() {
+            return;
+        }
+
+        let text = self.as_string();
+        let ranges = text
+            .match_indices(pat)
+            .map(|(start, m)| (start, start + m.len(), style));
+        self._style_ranges(ranges, MergeMode::Add);
+    }
+
+    chaining_fn!(TermString, highlight,
+                 pub fn with_highlighted<IT>(mut self, pat: &str, style: IT) -> Self
+                 where
+                     IT: Into<TermStyle>,
+                 {
+                     self.highlight(pat, style);
+                     self
+                 }
+    );
+}
+
+/// Methods for highlighting regex matches in a [`TermString`].
+///
+/// These are only available with the `regex` feature enabled.
+///
+/// The same rules of [`highlight()`] apply.
+///
+/// [`highlight()`]: TermString::highlight
+#[cfg(feature = "regex")]
+impl TermString {
+    /// Highlight all matches of `re` with `style`.
+    ///
+    /// # Examples
+    ///
+    /// ``` rust
+    /// extern crate regex;
+    /// # extern crate term_string;
+    /// use regex::Regex;
+    /// use term_string::{TermString, TermStyle};
+    ///
+    /// # fn main() {
+    /// let re = Regex::new(r"\d+").unwrap();
+    /// let mut ts = TermString::from("line 42, col 7");
+    /// ts.highlight_regex(&re, TermStyle::bold());
+    /// assert_eq!(ts.to_ansi_string(), "line \x1b[1m42\x1b[0m, col \x1b[1m7\x1b[0m");
+    /// # }
+    /// ```
+    pub fn highlight_regex<IT>(&mut self, re: &Regex, style: IT)
+    where
+        IT: Into<TermStyle>,
+    {
+        let style = style.into();
+        let text = self.as_string();
+
+        let ranges = re.find_iter(&text).map(|m| (m.start(), m.end(), style));
+        self._style_ranges(ranges, MergeMode::Add);
+    }
+
+    /// Highlight all matches of `re`, styling each capture group
+    /// with its own style.
+    ///
+    /// `styles[0]` is used for whole matches, `styles[1]` for the first
+    /// capture group, and so on. Group styles are added on top of the
+    /// whole match style. Groups without a style, or that didn't
+    /// participate in a match, are left as-is.
+    ///
+    /// # Examples
+    ///
+    /// ``` rust
+    /// extern crate regex;
+    /// # extern crate term_string;
+    /// use regex::Regex;
+    /// use term_string::{color, TermString, TermStyle};
+    ///
+    /// # fn main() {
+    /// let re = Regex::new(r"(\w+)=(\w+)").unwrap();
+    /// let styles = [TermStyle::underline(true), TermStyle::fg(color::BLUE), TermStyle::bold()];
+    /// let mut ts = TermString::from("set key=value");
+    /// ts.highlight_regex_captures(&re, &styles);
+    /// assert_eq!(
+    ///     ts.to_ansi_string(),
+    ///     "set \x1b[4;34mkey\x1b[39m=\x1b[1mvalue\x1b[0m"
+    /// );
+    /// # }
+    /// ```
+    pub fn highlight_regex_captures(&mut self, re: &Regex, styles: &[TermStyle]) {
+        let text = self.as_string();
+
+        // Groups may nest, so each match is split at group boundaries,
+        // and each part gets the styles of the groups containing it.
+        let ranges = re.captures_iter(&text).flat_map(|caps| {
+            let groups: Vec<_> = caps
+                .iter()
+                .zip(styles)
+                .filter_map(|(m, &style)| m.map(|m| (m.start(), m.end(), style)))
+                .collect();
+
+            let mut bounds: Vec<_> = groups.iter().flat_map(|g| vec![g.0, g.1]).collect();
+            bounds.sort_unstable();
+            bounds.dedup();
+
+            bounds
+                .windows(2)
+                .map(|part| {
+                    let style = groups
+                        .iter()
+                        .filter(|g| g.0 <= part[0] && part[1] <= g.1)
+                        .fold(TermStyle::default(), |style, g| style + g.2);
+                    (part[0], part[1], style)
+                })
+                .collect::<Vec<_>>()
+        });
+        self._style_ranges(ranges, MergeMode::Add);
+    }
+
+    chaining_fn!(TermString, highlight_regex,
+                 pub fn with_highlighted_regex<IT>(mut self, re: &Regex, style: IT) -> Self
+                 where
+                     IT: Into<TermStyle>,
+                 {
+                     self.highlight_regex(re, style);
+                     self
+                 }
+    );
+
+    chaining_fn!(TermString, highlight_regex_captures,
+                 pub fn with_highlighted_regex_captures(mut self, re: &Regex, styles: &[TermStyle]) -> Self {
+                     self.highlight_regex_captures(re, styles);
+                     self
+                 }
+    );
+}
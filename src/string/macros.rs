@@ -21,6 +21,8 @@ macro_rules! gen_idents {
                 m[$t "ln_plain"] = $t ln_plain;
                 m[$t "ln_styled"] = $t ln_styled;
                 m[$t "ln"] = $t ln;
+                m[$t "with"] = $t _with;
+                m[$t "ln_with"] = $t ln_with;
                 m[$t "isatty"] = $t _isatty;
         )* }
     );
@@ -85,7 +87,52 @@ macro_rules! gen_print_fns {
             gen_fn_with_doc!(
                 concat!(
                     "Print [`TermString`] to `", stringify!($dev), "` with styling\n",
-                    "if `", stringify!($dev), "` is a tty, and without if it's not.\n\n",
+                    "if `choice` says so, and without if not.\n\n",
+                    "With [`ColorChoice::Auto`], the environment is checked, then whether\n",
+                    "`", stringify!($dev), "` is a tty. Check out [`ColorChoice::should_style()`].\n\n",
+                    "If styling is forced (e.g. with [`ColorChoice::Always`]), and terminfo\n",
+                    "for the terminal can't be loaded, ANSI escape sequences are written,\n",
+                    "as with [`write_ansi()`].\n\n",
+                    "# Examples\n",
+                    "``` rust\n",
+                    "# use term_string::{ColorChoice, TermString, TermStyle};\n",
+                    "let bold = TermStyle::bold();\n",
+                    "let ts = TermString::new(bold, \"some bold text\");\n\n",
+                    "// This will write \"some bold text\" to ", stringify!($dev), " as bold\n",
+                    "// text, even if ", stringify!($dev), " is not a tty.\n",
+                    "ts.", stringify!($print "with"), "(ColorChoice::Always);\n",
+                    "```\n\n",
+                    "[`ColorChoice::Always`]: ::ColorChoice::Always\n",
+                    "[`ColorChoice::Auto`]: ::ColorChoice::Auto\n",
+                    "[`ColorChoice::should_style()`]: ::ColorChoice::should_style\n",
+                    "[`write_ansi()`]: TermString::write_ansi"
+                ),
+                pub fn $print "with"(&self, choice: ColorChoice) {
+                    if !choice.should_style(isatty::$dev "isatty"()) {
+                        return self.$print "plain"();
+                    }
+
+                    // Styling is forced if it's chosen for a non-tty
+                    let ansi_fallback = choice.should_style(false);
+
+                    #[cfg(windows)]
+                    let _ = self._write_styled_ret_out(io::$dev(), ansi_fallback);
+                    #[cfg(not(windows))]
+                    {
+                        let $dev = io::$dev();
+                        let _ = self._write_styled_ret_out($dev.lock(), ansi_fallback);
+                    }
+                }
+            );
+
+            gen_fn_with_doc!(
+                concat!(
+                    "Print [`TermString`] to `", stringify!($dev), "` with styling\n",
+                    "if the global [`ColorChoice`] says so, and without if not.\n\n",
+                    "With the default global choice ([`ColorChoice::Auto`]), this means\n",
+                    "with styling if `", stringify!($dev), "` is a tty, and without if it's not,\n",
+                    "unless the environment says otherwise (e.g. `NO_COLOR` is set).\n\n",
+                    "This is equivalent to `ts.", stringify!($print "with"), "(ColorChoice::global())`.\n\n",
                     "# Examples\n",
                     "``` rust\n",
                     "# use term_string::{TermString, TermStyle};\n",
@@ -94,14 +141,12 @@ macro_rules! gen_print_fns {
                     "// This will write \"some bold text\" to ", stringify!($dev), " as bold\n",
                     "// text if ", stringify!($dev), " is a tty, and without any formatting if not.\n",
                     "ts.", stringify!($print), "();\n",
-                    "```"
+                    "```\n\n",
+                    "[`ColorChoice`]: ::ColorChoice\n",
+                    "[`ColorChoice::Auto`]: ::ColorChoice::Auto"
                 ),
                 pub fn $print(&self) {
-                    if isatty::$dev "isatty"() {
-                        self.$print "styled"()
-                    } else {
-                        self.$print "plain"()
-                    }
+                    self.$print "with"(ColorChoice::global())
                 }
             );
 
@@ -138,6 +183,17 @@ macro_rules! gen_print_fns {
                     Self::from("\n").$print "plain"();
                 }
             );
+
+            gen_fn_with_doc!(
+                concat!(
+                    "The same as [`", stringify!($print "with"), "()`], but with a newline printed at the end.\n\n",
+                    "[`", stringify!($print "with"), "()`]: TermString::", stringify!($print "with")
+                ),
+                pub fn $print "ln_with"(&self, choice: ColorChoice) {
+                    self.$print "with"(choice);
+                    Self::from("\n").$print "plain"();
+                }
+            );
         }
     };
 }
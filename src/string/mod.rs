@@ -13,6 +13,23 @@
 mod tests;
 #[macro_use]
 mod macros;
+mod ansi;
+mod collect;
+mod edit;
+mod highlight;
+mod replace;
+mod sanitize;
+mod slice;
+mod spans;
+mod split;
+mod style_range;
+mod width;
+mod wrap;
+
+pub use self::ansi::ControlPolicy;
+pub use self::replace::Replacement;
+pub use self::sanitize::{SanitizeMode, Sanitizer};
+pub use self::style_range::MergeMode;
 
 pub use term::{
     terminfo::{TermInfo, TerminfoTerminal},
@@ -25,11 +42,14 @@ pub use term::{WinConsole, WinConsoleInfo};
 use isatty;
 
 use std::borrow::Borrow;
+use std::fmt;
+use std::hash::{Hash, Hasher};
 use std::io::{self, Write};
 use std::ops::{Add, AddAssign};
 
+use choice::ColorChoice;
 use error::Result;
-use style::TermStyle;
+use style::{Attr, ColorDepth, Rgb, TermStyle};
 
 enum Either<T, U> {
     A(T),
@@ -63,29 +83,112 @@ impl TermStringElement {
     }
 }
 
+fn write_rgb<W: TermWrite>(out: &mut W, sgr: u8, rgb: Rgb) -> Result<()> {
+    let Rgb(r, g, b) = rgb;
+    write!(out, "\x1b[{};2;{};{};{}m", sgr, r, g, b)?;
+    Ok(())
+}
+
 impl TermStringElement {
-    fn try_write_styled<W, TERM>(&self, out: &mut TERM) -> Result<()>
+    // Write the attrs changing the terminal from prev to style. A reset is
+    // needed if prev is not known, or if an attr has to be turned off, and
+    // terminfo has no off code for it (e.g. bold, or colors).
+    fn try_write_transition<W, TERM>(
+        out: &mut TERM,
+        prev: Option<TermStyle>,
+        style: TermStyle,
+    ) -> Result<()>
     where
         W: TermWrite,
         TERM: Terminal<Output = W>,
     {
-        // It's important to reset so text with empty style does not inherit attrs
-        out.reset()?;
+        let needs_reset = |prev: &TermStyle| {
+            let off_unsupported = |attr| !out.supports_attr(attr);
 
-        for attr in self.style.attrs.iter().filter_map(|&attr| attr) {
-            if out.supports_attr(attr) {
-                out.attr(attr)?;
+            let rgbs = prev._rgbs().map(|(attr, _)| attr);
+
+            prev.iter()
+                .filter(|&attr| !style.has_exact_attr(attr))
+                .chain(rgbs)
+                .any(|attr| match attr {
+                    Attr::Italic(true) => off_unsupported(Attr::Italic(false)),
+                    Attr::Underline(true) => off_unsupported(Attr::Underline(false)),
+                    // The standout off code often turns off reverse too
+                    Attr::Standout(true) => {
+                        off_unsupported(Attr::Standout(false))
+                            || style.has_exact_attr(Attr::Reverse)
+                    },
+                    Attr::Italic(false) | Attr::Underline(false) | Attr::Standout(false) => false,
+                    // A color can be replaced, but not turned off
+                    Attr::ForegroundColor(_) | Attr::BackgroundColor(_) => {
+                        !style.has_variant_attr(attr)
+                    },
+                    _ => true,
+                })
+        };
+
+        let prev = match prev {
+            Some(prev) if !needs_reset(&prev) => prev,
+            // It's important to reset so text with empty style does not inherit attrs
+            _ => {
+                out.reset()?;
+                TermStyle::default()
+            },
+        };
+
+        // Off codes first, so they can't undo attrs turned on (e.g. rmso
+        // turning off reverse)
+        for &(on, off) in &[
+            (Attr::Italic(true), Attr::Italic(false)),
+            (Attr::Underline(true), Attr::Underline(false)),
+            (Attr::Standout(true), Attr::Standout(false)),
+        ] {
+            if prev.has_exact_attr(on) && !style.has_variant_attr(on) {
+                out.attr(off)?;
             }
         }
 
-        write!(out, "{}", self.text)?;
+        for attr in style.iter() {
+            if !prev.has_exact_attr(attr) && out.supports_attr(attr) {
+                out.attr(attr)?;
+            }
+        }
 
-        // Ignore the error here to avoid double writes
-        let _ = out.reset();
+        // Terminal doesn't know about rgb colors, so we write them ourselves.
+        // They are only left after downsampling with truecolor support.
+        for (attr, rgb) in style._rgbs() {
+            if !prev.has_exact_rgb(attr, rgb) {
+                let sgr = match attr {
+                    Attr::ForegroundColor(_) => 38,
+                    _ => 48,
+                };
+                write_rgb(out.get_mut(), sgr, rgb)?;
+            }
+        }
 
         Ok(())
     }
 
+    // Returns the style the terminal is left in
+    fn try_write_styled<W, TERM>(
+        &self,
+        out: &mut TERM,
+        prev: Option<TermStyle>,
+        depth: ColorDepth,
+    ) -> Result<TermStyle>
+    where
+        W: TermWrite,
+        TERM: Terminal<Output = W>,
+    {
+        // Colors the terminal can't display are mapped to the nearest ones it can
+        let style = self.style.with_downsampled(depth);
+
+        Self::try_write_transition(out, prev, style)?;
+        write!(out, "{}", self.text)?;
+
+        Ok(style)
+    }
+
     fn write_plain<W>(&self, out: &mut W)
     where
         W: TermWrite,
@@ -93,13 +196,22 @@ impl TermStringElement {
         write!(out, "{}", &self.text).expect("should never happen");
     }
 
-    fn write_styled<W, TERM>(&self, out_term: &mut TERM)
-    where
+    // prev is the style the terminal is in, if known
+    fn write_styled<W, TERM>(
+        &self,
+        out_term: &mut TERM,
+        prev: &mut Option<TermStyle>,
+        depth: ColorDepth,
+    ) where
         W: TermWrite,
         TERM: Terminal<Output = W>,
     {
-        if self.try_write_styled(out_term).is_err() {
-            self.write_plain(out_term.get_mut());
+        match self.try_write_styled(out_term, *prev, depth) {
+            Ok(style) => *prev = Some(style),
+            Err(_) => {
+                *prev = None;
+                self.write_plain(out_term.get_mut());
+            },
         }
     }
 }
@@ -313,7 +425,10 @@ impl TermString {
         IS: Into<Self>,
     {
         let mut other = other.into();
-        self.elements.retain(|e| *e != TermStringElement::default());
+        // Only self's tail is checked, so appending stays O(len(other))
+        while self.elements.last() == Some(&TermStringElement::default()) {
+            self.elements.pop();
+        }
         other
             .elements
             .retain(|e| *e != TermStringElement::default());
@@ -376,6 +491,94 @@ impl TermString {
     );
 }
 
+// Internals
+impl TermString {
+    // The part of TermString in the byte range [start, end), with styles kept.
+    // start and end must be on char boundaries.
+    fn _slice(&self, start: usize, end: usize) -> Self {
+        let mut ts = Self::default();
+        let mut e_start = 0;
+
+        for e in &self.elements {
+            let e_end = e_start + e.text.len();
+            let (s, t) = (start.max(e_start), end.min(e_end));
+
+            if s < t {
+                ts.append_term_str(Self::new(e.style, &e.text[s - e_start..t - e_start]));
+            }
+
+            e_start = e_end;
+        }
+
+        ts
+    }
+
+    // The style of the last element, which append_str() inherits.
+    fn _last_style(&self) -> TermStyle {
+        self.elements.last().map(|e| e.style).unwrap_or_default()
+    }
+
+    // The style of the text at byte idx, or of the last element if idx
+    // is the end of TermString.
+    fn _style_at(&self, idx: usize) -> TermStyle {
+        let mut e_start = 0;
+
+        for e in &self.elements {
+            let e_end = e_start + e.text.len();
+            if idx < e_end {
+                return e.style;
+            }
+            e_start = e_end;
+        }
+
+        self._last_style()
+    }
+
+    // Split the element containing byte idx at idx, if idx is inside it.
+    // Returns the position of the first element starting at idx.
+    // idx must be on a char boundary.
+    fn _split_element_at(&mut self, idx: usize) -> usize {
+        let mut e_start = 0;
+
+        for pos in 0..self.elements.len() {
+            let e_len = self.elements[pos].text.len();
+
+            if idx == e_start {
+                return pos;
+            } else if idx < e_start + e_len {
+                let style = self.elements[pos].style;
+                let text = self.elements[pos].text.split_off(idx - e_start);
+                self.elements.insert(pos + 1, TermStringElement { style, text });
+                return pos + 1;
+            }
+
+            e_start += e_len;
+        }
+
+        self.elements.len()
+    }
+
+    // Merge adjacent elements with equal styles, and remove empty elements.
+    // An empty last element is kept, as its style is inherited by appended text.
+    fn _normalize(&mut self) {
+        let last_pos = self.elements.len().saturating_sub(1);
+        let mut elements: Vec<TermStringElement> = Vec::with_capacity(self.elements.len());
+
+        for (pos, e) in self.elements.drain(..).enumerate() {
+            if e.text.is_empty() && pos != last_pos {
+                continue;
+            }
+
+            match elements.last_mut() {
+                Some(prev) if prev.style == e.style => prev.text += &e.text,
+                _ => elements.push(e),
+            }
+        }
+
+        self.elements = elements;
+    }
+}
+
 // Style
 /// Method for modifying the style of all internal elements of a [`TermString`].
 ///
@@ -539,9 +742,13 @@ gen_idents!(print, eprint, stdout, stderr);
 /// `TermWrite` is bound to `Write + Send` on Windows, and only `Write`
 /// on other platforms.
 impl TermString {
-    fn term_or_w<W: TermWrite>(out: W) -> Either<TerminfoTerminal<W>, W> {
+    // Also returns the color depth of the terminal
+    fn term_or_w<W: TermWrite>(out: W) -> Either<(TerminfoTerminal<W>, ColorDepth), W> {
         match TermInfo::from_env() {
-            Ok(ti) => Either::A(TerminfoTerminal::new_with_terminfo(out, ti)),
+            Ok(ti) => {
+                let depth = ColorDepth::from_terminfo(&ti);
+                Either::A((TerminfoTerminal::new_with_terminfo(out, ti), depth))
+            },
             Err(_) => Either::B(out),
         }
     }
@@ -580,36 +787,59 @@ impl TermString {
         out
     }
 
+    // Only the attrs changing between adjacent elements are written.
+    fn _write_elements<W, TERM>(&self, out_term: &mut TERM, depth: ColorDepth)
+    where
+        W: TermWrite,
+        TERM: Terminal<Output = W>,
+    {
+        let mut prev = None;
+
+        for e in self.elements.iter().filter(|e| !e.text.is_empty()) {
+            e.write_styled(out_term, &mut prev, depth);
+        }
+
+        // Ignore the error here to avoid double writes
+        if prev != Some(TermStyle::default()) && !self.is_empty() {
+            let _ = out_term.reset();
+        }
+    }
+
+    // With ansi_fallback, ANSI escapes are written if the terminal is
+    // not known, instead of plain text.
+    fn _write_fallback_ret_out<W: TermWrite>(&self, out: W, ansi_fallback: bool) -> W {
+        if ansi_fallback {
+            self.write_ansi_ret_out(out)
+        } else {
+            self.write_plain_ret_out(out)
+        }
+    }
+
     #[cfg(not(windows))]
-    fn _write_styled_ret_out<W: TermWrite>(&self, out: W) -> W {
+    fn _write_styled_ret_out<W: TermWrite>(&self, out: W, ansi_fallback: bool) -> W {
         match Self::term_or_w(out) {
-            Either::A(mut out_term) => {
-                for e in &self.elements {
-                    e.write_styled(&mut out_term);
-                }
+            Either::A((mut out_term, depth)) => {
+                self._write_elements(&mut out_term, depth);
                 return out_term.into_inner();
             },
-            Either::B(out) => self.write_plain_ret_out(out),
+            Either::B(out) => self._write_fallback_ret_out(out, ansi_fallback),
         }
     }
 
     #[cfg(windows)]
-    fn _write_styled_ret_out<W: TermWrite>(&self, out: W) -> W {
+    fn _write_styled_ret_out<W: TermWrite>(&self, out: W, ansi_fallback: bool) -> W {
         match Self::term_or_w(out) {
-            Either::A(mut out_term) => {
-                for e in &self.elements {
-                    e.write_styled(&mut out_term);
-                }
+            Either::A((mut out_term, depth)) => {
+                self._write_elements(&mut out_term, depth);
                 return out_term.into_inner();
             },
             Either::B(out) => match Self::console_or_w(out) {
                 Either::A(mut out_term) => {
-                    for e in &self.elements {
-                        e.write_styled(&mut out_term);
-                    }
+                    // The Windows console only has the base 16 colors
+                    self._write_elements(&mut out_term, ColorDepth::Ansi16);
                     return out_term.into_inner();
                 },
-                Either::B(mut out) => self.write_plain_ret_out(out),
+                Either::B(out) => self._write_fallback_ret_out(out, ansi_fallback),
             },
         }
     }
@@ -650,13 +880,76 @@ impl TermString {
     ///
     /// [`write_styled()`]: TermString::write_styled
     pub fn write_styled_ret_out<W: TermWrite>(&self, out: W) -> W {
-        self._write_styled_ret_out(out)
+        self._write_styled_ret_out(out, false)
     }
 
     gen_print_fns!(stdout, print);
     gen_print_fns!(stderr, eprint);
 }
 
+/// Format [`TermString`] with ANSI escape sequences, as returned by
+/// [`to_ansi_string()`]. The alternate flag (`{:#}`) formats the
+/// un-styled string instead, as returned by [`as_string()`].
+///
+/// The global [`ColorChoice`] is respected. Since the destination is not
+/// known here, and may well be a file (e.g. a log), [`ColorChoice::Auto`]
+/// formats with escape sequences only if the environment forces it (i.e.
+/// `CLICOLOR_FORCE` is set). This is what [`should_style(false)`] returns.
+///
+/// A width (e.g. `{:>10}`) pads to that many columns, as with
+/// [`pad_left()`], [`pad_right()`] and [`center()`], using the fill
+/// character (a space by default). Text is aligned to the left by default.
+/// Precision is ignored, use [`truncate_to_width()`] instead.
+///
+/// [`to_ansi_string()`]: TermString::to_ansi_string
+/// [`as_string()`]: TermString::as_string
+/// [`pad_left()`]: TermString::pad_left
+/// [`pad_right()`]: TermString::pad_right
+/// [`center()`]: TermString::center
+/// [`truncate_to_width()`]: TermString::truncate_to_width
+/// [`ColorChoice`]: ::ColorChoice
+/// [`ColorChoice::Auto`]: ::ColorChoice::Auto
+/// [`should_style(false)`]: ::ColorChoice::should_style
+///
+/// # Examples
+///
+/// ``` rust
+/// use term_string::{color, ColorChoice, TermString, TermStyle};
+///
+/// // e.g. --color=always
+/// ColorChoice::set_global(ColorChoice::Always);
+///
+/// let ts = TermString::new(TermStyle::fg(color::RED), "red");
+/// assert_eq!(format!("{}", ts), "\x1b[31mred\x1b[0m");
+/// assert_eq!(format!("{:#}", ts), "red");
+///
+/// let ts = TermString::from("日本");
+/// assert_eq!(format!("{:>6}|{:-^7}|", ts, ts), "  日本|-日本--|");
+/// ```
+impl fmt::Display for TermString {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let padded;
+        let ts = match f.width() {
+            Some(width) => {
+                let (ts, fill) = (self.clone(), f.fill().to_string());
+                padded = match f.align() {
+                    Some(fmt::Alignment::Right) => ts.with_padded_left(width, fill),
+                    Some(fmt::Alignment::Center) => ts.with_centered(width, fill),
+                    _ => ts.with_padded_right(width, fill),
+                };
+                &padded
+            },
+            None => self,
+        };
+
+        if f.alternate() || !ColorChoice::global().should_style(false) {
+            ts.elements.iter().try_for_each(|e| f.write_str(&e.text))
+        } else {
+            f.write_str(&ts.to_ansi_string())
+        }
+    }
+}
+
 impl<S> From<S> for TermString
 where
     S: Borrow<str>,
@@ -697,3 +990,51 @@ impl AddAssign for TermString {
         self.append_term_str(other);
     }
 }
+
+/// [`TermString`]s are equal if they have the same text, with the same
+/// styles. How the text is split into spans doesn't matter, and neither
+/// do the styles of empty spans, except for an empty last span. Its style
+/// is the one [`append_str()`] inherits, so it's compared too.
+///
+/// [`append_str()`]: TermString::append_str
+///
+/// # Examples
+///
+/// ``` rust
+/// # use term_string::{TermString, TermStyle};
+/// let ts1 = TermString::new(TermStyle::bold(), "some bold") + TermString::from(" text");
+/// let ts2 = TermString::new(TermStyle::bold(), "some") + TermString::new(TermStyle::bold(), " bold");
+/// assert_eq!(ts1, ts2 + TermString::from(" text"));
+/// assert_ne!(ts1, TermString::from("some bold text"));
+///
+/// // "x" would be bold if appended to the first
+/// assert_ne!(TermString::new(TermStyle::bold(), ""), TermString::default());
+/// assert_eq!(TermString::from(""), TermString::default());
+/// ```
+impl PartialEq for TermString {
+    fn eq(&self, other: &Self) -> bool {
+        self.styled_chars().eq(other.styled_chars())
+            && self._last_style() == other._last_style()
+    }
+}
+
+impl Eq for TermString {}
+
+/// Consistent with equality, so [`TermString`]s can be used as keys in
+/// maps and sets.
+impl Hash for TermString {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Each style is hashed once per run of text, wherever it's split
+        let mut prev_style = None;
+
+        for (c, style) in self.styled_chars() {
+            if prev_style != Some(style) {
+                style.hash(state);
+                prev_style = Some(style);
+            }
+            c.hash(state);
+        }
+
+        self._last_style().hash(state);
+    }
+}
@@ -0,0 +1,210 @@
+/*
+    This file is a part of term-string.
+
+    Copyright (C) 2018 Mohammad AlSaleh <CE.Mohammad.AlSaleh at gmail.com>
+    https://github.com/rust-alt/term-string
+
+    This Source Code Form is subject to the terms of the Mozilla Public
+    License, v. 2.0. If a copy of the MPL was not distributed with this
+    file, You can obtain one at <http://mozilla.org/MPL/2.0/>.
+*/
+
+use style::TermStyle;
+
+use super::{TermString, TermStringElement};
+
+/// A value matches can be replaced with, in [`TermString::replace()`]
+/// and [`TermString::replacen()`].
+///
+/// Plain strings inherit the style at the match position, while
+/// [`TermString`]s keep their own styles.
+///
+/// [`TermString::replace()`]: TermString::replace
+/// [`TermString::replacen()`]: TermString::replacen
+pub trait Replacement {
+    /// Return the [`TermString`] replacing a match, `style` being
+    /// the style at the match position.
+    fn replacement(&self, style: TermStyle) -> TermString;
+}
+
+impl Replacement for &str {
+    fn replacement(&self, style: TermStyle) -> TermString {
+        TermString::new(style, *self)
+    }
+}
+
+impl Replacement for String {
+    fn replacement(&self, style: TermStyle) -> TermString {
+        TermString::new(style, self.as_str())
+    }
+}
+
+impl Replacement for TermString {
+    fn replacement(&self, _: TermStyle) -> TermString {
+        self.clone()
+    }
+}
+
+impl Replacement for &TermString {
+    fn replacement(&self, _: TermStyle) -> TermString {
+        (*self).clone()
+    }
+}
+
+// Walks elements in order, for taking parts of them at increasing
+// byte offsets in one pass.
+struct Walker<'a> {
+    elements: &'a [TermStringElement],
+    pos: usize,
+    e_start: usize,
+}
+
+impl<'a> Walker<'a> {
+    fn new(elements: &'a [TermStringElement]) -> Self {
+        Walker {
+            elements,
+            pos: 0,
+            e_start: 0,
+        }
+    }
+
+    // The parts of the elements in the byte range [start, end), which
+    // must not start before the end of the previously taken range.
+    fn take(&mut self, start: usize, end: usize) -> Vec<TermStringElement> {
+        let mut parts = Vec::new();
+
+        while let Some(e) = self.elements.get(self.pos) {
+            let e_end = self.e_start + e.text.len();
+            let (s, t) = (start.max(self.e_start), end.min(e_end));
+
+            if s < t {
+                let text = &e.text[s - self.e_start..t - self.e_start];
+                parts.push(TermStringElement::new(e.style, text));
+            }
+
+            // The element may continue in the next range
+            if e_end > end {
+                break;
+            }
+
+            self.pos += 1;
+            self.e_start = e_end;
+        }
+
+        parts
+    }
+
+    // The style of the text at byte idx, or of the last element if idx
+    // is the end of the elements.
+    fn style_at(&mut self, idx: usize) -> TermStyle {
+        while let Some(e) = self.elements.get(self.pos) {
+            let e_end = self.e_start + e.text.len();
+            if idx < e_end {
+                return e.style;
+            }
+
+            self.pos += 1;
+            self.e_start = e_end;
+        }
+
+        self.elements.last().map(|e| e.style).unwrap_or_default()
+    }
+}
+
+/// Methods for replacing matches in a [`TermString`].
+///
+/// Like their [`str`] counterparts, these return a new [`TermString`].
+/// Matches are found in the un-styled string, and text outside matches
+/// keeps its style.
+impl TermString {
+    fn _replace<F>(&self, pat: &str, count: Option<usize>, mut f: F) -> Self
+    where
+        F: FnMut(&Self, TermStyle) -> Self,
+    {
+        let text = self.as_string();
+        let mut walker = Walker::new(&self.elements);
+        let mut ts = Self::default();
+        let mut last_end = 0;
+
+        let matches = text.match_indices(pat).take(count.unwrap_or(usize::MAX));
+        for (start, m) in matches {
+            let end = start + m.len();
+            ts.elements.extend(walker.take(last_end, start));
+            let style = walker.style_at(start);
+            let m = Self { elements: walker.take(start, end) };
+            ts.elements.extend(f(&m, style).elements);
+            last_end = end;
+        }
+
+        ts.elements.extend(walker.take(last_end, text.len()));
+        ts._normalize();
+        ts
+    }
+
+    /// Replace all matches of `from` with `to`.
+    ///
+    /// `to` can be a plain string, inheriting the style at the match
+    /// position, or a [`TermString`], keeping its own styles.
+    /// Check out [`Replacement`].
+    ///
+    /// [`Replacement`]: ::Replacement
+    ///
+    /// # Examples
+    ///
+    /// ``` rust
+    /// use term_string::{color, TermString, TermStyle};
+    ///
+    /// let template = TermString::new(TermStyle::bold(), "error:") + TermString::from(" {file} not found");
+    ///
+    /// let ts = template.replace("{file}", "foo.rs");
+    /// assert_eq!(ts.to_ansi_string(), "\x1b[1merror:\x1b[0m foo.rs not found");
+    ///
+    /// let file = TermString::new(TermStyle::fg(color::BLUE), "foo.rs");
+    /// let ts = template.replace("{file}", file);
+    /// assert_eq!(ts.to_ansi_string(), "\x1b[1merror:\x1b[0m \x1b[34mfoo.rs\x1b[0m not found");
+    /// ```
+    pub fn replace<R>(&self, from: &str, to: R) -> Self
+    where
+        R: Replacement,
+    {
+        self._replace(from, None, |_, style| to.replacement(style))
+    }
+
+    /// Replace the first `count` matches of `from` with `to`.
+    ///
+    /// Check out [`replace()`] for details.
+    ///
+    /// [`replace()`]: TermString::replace
+    ///
+    /// # Examples
+    ///
+    /// ``` rust
+    /// # use term_string::{TermString, TermStyle};
+    /// let ts = TermString::new(TermStyle::bold(), "a-b-c");
+    /// assert_eq!(ts.replacen("-", "+", 1).as_string(), "a+b-c");
+    /// ```
+    pub fn replacen<R>(&self, from: &str, to: R, count: usize) -> Self
+    where
+        R: Replacement,
+    {
+        self._replace(from, Some(count), |_, style| to.replacement(style))
+    }
+
+    /// Replace all matches of `pat` with the return value of `f`,
+    /// called with each match.
+    ///
+    /// # Examples
+    ///
+    /// ``` rust
+    /// # use term_string::{TermString, TermStyle};
+    /// let ts = TermString::from("warn: check this warn");
+    /// let ts = ts.replace_with("warn", |m| TermString::new(TermStyle::bold(), m.as_string().to_uppercase()));
+    /// assert_eq!(ts.to_ansi_string(), "\x1b[1mWARN\x1b[0m: check this \x1b[1mWARN\x1b[0m");
+    /// ```
+    pub fn replace_with<F>(&self, pat: &str, mut f: F) -> Self
+    where
+        F: FnMut(&Self) -> Self,
+    {
+        self._replace(pat, None, |m, _| f(m))
+    }
+}
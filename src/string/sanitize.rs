@@ -0,0 +1,250 @@
+/*
+    This file is a part of term-string.
+
+    Copyright (C) 2018 Mohammad AlSaleh <CE.Mohammad.AlSaleh at gmail.com>
+    https://github.com/rust-alt/term-string
+
+    This Source Code Form is subject to the terms of the Mozilla Public
+    License, v. 2.0. If a copy of the MPL was not distributed with this
+    file, You can obtain one at <http://mozilla.org/MPL/2.0/>.
+*/
+
+use std::borrow::Borrow;
+
+use style::TermStyle;
+
+use super::ansi::{Token, Tokens, ESC};
+use super::TermString;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+/// What a [`Sanitizer`] does with control characters.
+///
+/// Control characters are C0 controls (`\x00`-`\x1f`, including ESC),
+/// DEL (`\x7f`), and C1 controls (`\u{80}`-`\u{9f}`).
+pub enum SanitizeMode {
+    /// Replace control characters with their Rust escapes (e.g. `\u{1b}`).
+    Escape,
+    /// Replace control characters with visible symbols. C0 controls and DEL
+    /// are replaced with their Unicode control pictures (e.g. `␛`), and C1
+    /// controls with `�`.
+    Replace,
+    /// Remove control characters. Whole escape sequences are removed,
+    /// unless ESC is allowed.
+    Remove,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+/// Neutralizes control characters in untrusted text (e.g. file names,
+/// or log lines), so writing the text can't inject escape sequences that
+/// clear the screen, spoof output, or set the window title.
+///
+/// Control characters in the allow-list are kept as-is.
+///
+/// # Examples
+/// ``` rust
+/// use term_string::{SanitizeMode, Sanitizer};
+///
+/// let text = "file\x1b[2J\tname\n";
+///
+/// // Replace mode, with `\t` and `\n` allowed
+/// let sanitizer = Sanitizer::default();
+/// assert_eq!(sanitizer.sanitize(text), "file␛[2J\tname\n");
+///
+/// let sanitizer = Sanitizer::new(SanitizeMode::Escape).with_allowed('\n');
+/// assert_eq!(sanitizer.sanitize(text), "file\\u{1b}[2J\\tname\n");
+///
+/// let sanitizer = Sanitizer::new(SanitizeMode::Remove);
+/// assert_eq!(sanitizer.sanitize(text), "filename");
+/// ```
+pub struct Sanitizer {
+    mode: SanitizeMode,
+    allowed: Vec<char>,
+}
+
+/// [`SanitizeMode::Replace`], with `\t` and `\n` allowed.
+///
+/// [`SanitizeMode::Replace`]: SanitizeMode::Replace
+impl Default for Sanitizer {
+    fn default() -> Self {
+        Self::new(SanitizeMode::Replace)
+            .with_allowed('\t')
+            .with_allowed('\n')
+    }
+}
+
+// The visible symbol replacing a control character.
+fn control_picture(c: char) -> char {
+    match c as u32 {
+        n @ 0x00..=0x1f => ::std::char::from_u32(0x2400 + n).expect("should never happen"),
+        0x7f => '\u{2421}',
+        _ => '\u{fffd}',
+    }
+}
+
+impl Sanitizer {
+    /// Create a [`Sanitizer`] with `mode`, and an empty allow-list.
+    pub fn new(mode: SanitizeMode) -> Self {
+        Self {
+            mode,
+            allowed: Vec::new(),
+        }
+    }
+
+    /// Add `c` to the allow-list.
+    pub fn allow(&mut self, c: char) {
+        if !self.allowed.contains(&c) {
+            self.allowed.push(c);
+        }
+    }
+
+    chaining_fn!(Sanitizer, allow,
+                 pub fn with_allowed(mut self, c: char) -> Self {
+                     self.allow(c);
+                     self
+                 }
+    );
+
+    fn _push_sanitized(&self, out: &mut String, text: &str) {
+        for c in text.chars() {
+            if !c.is_control() || self.allowed.contains(&c) {
+                out.push(c);
+                continue;
+            }
+
+            match self.mode {
+                SanitizeMode::Escape => out.extend(c.escape_default()),
+                SanitizeMode::Replace => out.push(control_picture(c)),
+                SanitizeMode::Remove => (),
+            }
+        }
+    }
+
+    /// Return `text` with control characters neutralized.
+    pub fn sanitize(&self, text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+
+        if self.mode == SanitizeMode::Remove && !self.allowed.contains(&ESC) {
+            for token in (Tokens { text }) {
+                if let Token::Text(text) = token {
+                    self._push_sanitized(&mut out, text);
+                }
+            }
+        } else {
+            self._push_sanitized(&mut out, text);
+        }
+
+        out
+    }
+}
+
+/// Methods for safely adding untrusted text to a [`TermString`].
+///
+/// Text added with [`new()`] or [`append_str()`] is written verbatim.
+/// So, text from untrusted sources (file names, log lines, network input)
+/// should be added with these methods instead.
+///
+/// [`new()`]: TermString::new
+/// [`append_str()`]: TermString::append_str
+impl TermString {
+    /// Create a [`TermString`] from a [`TermStyle`] and untrusted text,
+    /// sanitized with [`Sanitizer::default()`].
+    ///
+    /// [`Sanitizer::default()`]: ::Sanitizer
+    ///
+    /// # Examples
+    ///
+    /// ``` rust
+    /// # use term_string::{TermString, TermStyle};
+    /// let file_name = "\x1b]0;pwned\x07.txt";
+    /// let ts = TermString::new_untrusted(TermStyle::bold(), file_name);
+    /// assert_eq!(ts.as_string(), "␛]0;pwned␇.txt");
+    /// ```
+    pub fn new_untrusted<S>(style: TermStyle, text: S) -> Self
+    where
+        S: Borrow<str>,
+    {
+        Self::new_sanitized(style, text, &Sanitizer::default())
+    }
+
+    /// Create a [`TermString`] from a [`TermStyle`] and untrusted text,
+    /// sanitized with `sanitizer`.
+    pub fn new_sanitized<S>(style: TermStyle, text: S, sanitizer: &Sanitizer) -> Self
+    where
+        S: Borrow<str>,
+    {
+        Self::new(style, sanitizer.sanitize(text.borrow()))
+    }
+
+    /// Append untrusted text, sanitized with [`Sanitizer::default()`],
+    /// inheriting the previous style.
+    ///
+    /// [`Sanitizer::default()`]: ::Sanitizer
+    ///
+    /// # Examples
+    ///
+    /// ``` rust
+    /// # use term_string::{TermString, TermStyle};
+    /// let mut ts = TermString::new(TermStyle::bold(), "log: ");
+    /// ts.append_untrusted("\x1b[2Jdone");
+    /// assert_eq!(ts.as_string(), "log: ␛[2Jdone");
+    /// ```
+    pub fn append_untrusted<S>(&mut self, text: S)
+    where
+        S: Borrow<str>,
+    {
+        self.append_sanitized(text, &Sanitizer::default());
+    }
+
+    /// Append untrusted text, sanitized with `sanitizer`, inheriting
+    /// the previous style.
+    pub fn append_sanitized<S>(&mut self, text: S, sanitizer: &Sanitizer)
+    where
+        S: Borrow<str>,
+    {
+        self.append_str(sanitizer.sanitize(text.borrow()));
+    }
+
+    /// Sanitize all text in [`TermString`] with `sanitizer`.
+    ///
+    /// # Examples
+    ///
+    /// ``` rust
+    /// # use term_string::{SanitizeMode, Sanitizer, TermString, TermStyle};
+    /// let mut ts = TermString::new(TermStyle::bold(), "a\x07") + TermString::from("b\x1b[Hc");
+    /// ts.sanitize(&Sanitizer::new(SanitizeMode::Remove));
+    /// assert_eq!(ts.as_string(), "abc");
+    /// ```
+    pub fn sanitize(&mut self, sanitizer: &Sanitizer) {
+        for e in &mut self.elements {
+            e.text = sanitizer.sanitize(&e.text);
+        }
+        self._normalize();
+    }
+
+    chaining_fn!(TermString, append_untrusted,
+                 pub fn with_appended_untrusted<S>(mut self, text: S) -> Self
+                 where
+                     S: Borrow<str>,
+                 {
+                     self.append_untrusted(text);
+                     self
+                 }
+    );
+
+    chaining_fn!(TermString, append_sanitized,
+                 pub fn with_appended_sanitized<S>(mut self, text: S, sanitizer: &Sanitizer) -> Self
+                 where
+                     S: Borrow<str>,
+                 {
+                     self.append_sanitized(text, sanitizer);
+                     self
+                 }
+    );
+
+    chaining_fn!(TermString, sanitize,
+                 pub fn with_sanitized(mut self, sanitizer: &Sanitizer) -> Self {
+                     self.sanitize(sanitizer);
+                     self
+                 }
+    );
+}
@@ -0,0 +1,271 @@
+/*
+    This file is a part of term-string.
+
+    Copyright (C) 2018 Mohammad AlSaleh <CE.Mohammad.AlSaleh at gmail.com>
+    https://github.com/rust-alt/term-string
+
+    This Source Code Form is subject to the terms of the Mozilla Public
+    License, v. 2.0. If a copy of the MPL was not distributed with this
+    file, You can obtain one at <http://mozilla.org/MPL/2.0/>.
+*/
+
+use std::ops::{Bound, RangeBounds};
+
+use super::TermString;
+
+// The [start, end) bounds of range, with len as the end of unbounded ranges.
+pub(crate) fn bounds<R: RangeBounds<usize>>(range: &R, len: usize) -> (usize, usize) {
+    let start = match range.start_bound() {
+        Bound::Included(&s) => s,
+        Bound::Excluded(&s) => s.saturating_add(1),
+        Bound::Unbounded => 0,
+    };
+
+    let end = match range.end_bound() {
+        Bound::Included(&e) => e.saturating_add(1),
+        Bound::Excluded(&e) => e,
+        Bound::Unbounded => len,
+    };
+
+    (start, end)
+}
+
+/// Methods for taking parts of a [`TermString`].
+///
+/// Offsets are byte offsets into the un-styled string, like with [`str`],
+/// except for the `char_*` methods which take char offsets. Parts keep
+/// the styles of the text they were taken from.
+///
+/// Note that [`TermString`] doesn't implement [`Index`] with ranges, since
+/// [`Index`] has to return a reference, and a part of a [`TermString`]
+/// is a new value. Use [`slice()`] instead.
+///
+/// [`Index`]: std::ops::Index
+/// [`slice()`]: TermString::slice
+impl TermString {
+    fn _is_char_boundary(&self, idx: usize) -> bool {
+        let mut e_start = 0;
+
+        for e in &self.elements {
+            if idx <= e_start + e.text.len() {
+                return e.text.is_char_boundary(idx - e_start);
+            }
+            e_start += e.text.len();
+        }
+
+        idx == e_start
+    }
+
+    fn _is_valid_range(&self, start: usize, end: usize) -> bool {
+        start <= end && self._is_char_boundary(start) && self._is_char_boundary(end)
+    }
+
+    // The bounds of a byte range. Panics if the range is invalid.
+    pub(crate) fn _checked_range<R>(&self, range: &R) -> (usize, usize)
+    where
+        R: RangeBounds<usize>,
+    {
+        let (start, end) = bounds(range, self.len());
+
+        if !self._is_valid_range(start, end) {
+            panic!(
+                "byte range {}..{} is out of bounds of, or not on char boundaries in, `{}`",
+                start,
+                end,
+                self.as_string()
+            );
+        }
+
+        (start, end)
+    }
+
+    // The byte bounds of a char range, or None if the range is invalid.
+    fn _char_range<R>(&self, range: &R) -> Option<(usize, usize)>
+    where
+        R: RangeBounds<usize>,
+    {
+        let char_count = self.char_count();
+        let (start, end) = bounds(range, char_count);
+
+        if start > end || end > char_count {
+            return None;
+        }
+
+        let byte_idx = |idx| self.char_indices().nth(idx).map_or(self.len(), |(i, _)| i);
+        Some((byte_idx(start), byte_idx(end)))
+    }
+
+    // The byte bounds of a char range. Panics if the range is invalid.
+    pub(crate) fn _checked_char_range<R>(&self, range: &R) -> (usize, usize)
+    where
+        R: RangeBounds<usize>,
+    {
+        self._char_range(range).unwrap_or_else(|| {
+            let (start, end) = bounds(range, self.char_count());
+            panic!(
+                "char range {}..{} is out of bounds of `{}`",
+                start,
+                end,
+                self.as_string()
+            )
+        })
+    }
+
+    /// Return the part of [`TermString`] in a byte range, or `None` if
+    /// the range is out of bounds, or not on char boundaries.
+    ///
+    /// # Examples
+    ///
+    /// ``` rust
+    /// # use term_string::{TermString, TermStyle};
+    /// let ts = TermString::new(TermStyle::bold(), "bold") + TermString::from(" café");
+    /// assert_eq!(ts.get(2..7).unwrap().as_string(), "ld ca");
+    /// assert!(ts.get(9..).is_none()); // inside 'é'
+    /// assert!(ts.get(..20).is_none());
+    /// ```
+    pub fn get<R>(&self, range: R) -> Option<Self>
+    where
+        R: RangeBounds<usize>,
+    {
+        let (start, end) = bounds(&range, self.len());
+
+        if self._is_valid_range(start, end) {
+            Some(self._slice(start, end))
+        } else {
+            None
+        }
+    }
+
+    /// Return the part of [`TermString`] in a byte range.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds, or not on char boundaries.
+    /// Check out [`get()`] for a non-panicking alternative.
+    ///
+    /// [`get()`]: TermString::get
+    ///
+    /// # Examples
+    ///
+    /// ``` rust
+    /// # use term_string::{TermString, TermStyle};
+    /// let ts = TermString::new(TermStyle::bold(), "bold") + TermString::from(" text");
+    /// let part = ts.slice(2..7);
+    /// assert_eq!(part.as_string(), "ld te");
+    /// ```
+    pub fn slice<R>(&self, range: R) -> Self
+    where
+        R: RangeBounds<usize>,
+    {
+        let (start, end) = self._checked_range(&range);
+        self._slice(start, end)
+    }
+
+    /// Return the part of [`TermString`] in a char range, or `None` if
+    /// the range is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ``` rust
+    /// # use term_string::{TermString, TermStyle};
+    /// let ts = TermString::new(TermStyle::bold(), "日本") + TermString::from("語");
+    /// assert_eq!(ts.char_get(1..).unwrap().as_string(), "本語");
+    /// assert!(ts.char_get(2..4).is_none());
+    /// ```
+    pub fn char_get<R>(&self, range: R) -> Option<Self>
+    where
+        R: RangeBounds<usize>,
+    {
+        self._char_range(&range).map(|(start, end)| self._slice(start, end))
+    }
+
+    /// Return the part of [`TermString`] in a char range.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds.
+    /// Check out [`char_get()`] for a non-panicking alternative.
+    ///
+    /// [`char_get()`]: TermString::char_get
+    ///
+    /// # Examples
+    ///
+    /// ``` rust
+    /// # use term_string::{TermString, TermStyle};
+    /// let ts = TermString::new(TermStyle::bold(), "日本") + TermString::from("語");
+    /// assert_eq!(ts.char_slice(1..).as_string(), "本語");
+    /// ```
+    pub fn char_slice<R>(&self, range: R) -> Self
+    where
+        R: RangeBounds<usize>,
+    {
+        let (start, end) = self._checked_char_range(&range);
+        self._slice(start, end)
+    }
+
+    /// Split [`TermString`] in two at a byte offset.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mid` is out of bounds, or not on a char boundary.
+    /// Check out [`split_at_checked()`] for a non-panicking alternative.
+    ///
+    /// [`split_at_checked()`]: TermString::split_at_checked
+    ///
+    /// # Examples
+    ///
+    /// ``` rust
+    /// # use term_string::{TermString, TermStyle};
+    /// let ts = TermString::new(TermStyle::bold(), "key") + TermString::from("=value");
+    /// let (key, value) = ts.split_at(3);
+    /// assert_eq!(key.as_string(), "key");
+    /// assert_eq!(value.as_string(), "=value");
+    /// ```
+    pub fn split_at(&self, mid: usize) -> (Self, Self) {
+        (self.slice(..mid), self.slice(mid..))
+    }
+
+    /// Split [`TermString`] in two at a byte offset, or return `None` if
+    /// `mid` is out of bounds, or not on a char boundary.
+    ///
+    /// # Examples
+    ///
+    /// ``` rust
+    /// # use term_string::{TermString, TermStyle};
+    /// let ts = TermString::new(TermStyle::bold(), "clé") + TermString::from("=value");
+    /// let (key, value) = ts.split_at_checked(4).unwrap();
+    /// assert_eq!(key.as_string(), "clé");
+    /// assert_eq!(value.as_string(), "=value");
+    /// assert!(ts.split_at_checked(3).is_none()); // inside 'é'
+    /// ```
+    pub fn split_at_checked(&self, mid: usize) -> Option<(Self, Self)> {
+        if self._is_char_boundary(mid) {
+            Some((self._slice(0, mid), self._slice(mid, self.len())))
+        } else {
+            None
+        }
+    }
+
+    /// Return an iterator over the chars of the un-styled string contained
+    /// in [`TermString`], and their byte offsets.
+    ///
+    /// This can be used to convert char offsets to byte ones.
+    ///
+    /// # Examples
+    ///
+    /// ``` rust
+    /// # use term_string::{TermString, TermStyle};
+    /// let ts = TermString::new(TermStyle::bold(), "é") + TermString::from("a");
+    /// let indices: Vec<_> = ts.char_indices().collect();
+    /// assert_eq!(indices, [(0, 'é'), (2, 'a')]);
+    /// ```
+    pub fn char_indices<'a>(&'a self) -> impl Iterator<Item = (usize, char)> + 'a {
+        let mut e_start = 0;
+
+        self.elements.iter().flat_map(move |e| {
+            let start = e_start;
+            e_start += e.text.len();
+            e.text.char_indices().map(move |(i, c)| (start + i, c))
+        })
+    }
+}
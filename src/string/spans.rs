@@ -0,0 +1,145 @@
+/*
+    This file is a part of term-string.
+
+    Copyright (C) 2018 Mohammad AlSaleh <CE.Mohammad.AlSaleh at gmail.com>
+    https://github.com/rust-alt/term-string
+
+    This Source Code Form is subject to the terms of the Mozilla Public
+    License, v. 2.0. If a copy of the MPL was not distributed with this
+    file, You can obtain one at <http://mozilla.org/MPL/2.0/>.
+*/
+
+use std::borrow::Borrow;
+use std::iter::FromIterator;
+
+use style::TermStyle;
+
+use super::TermString;
+
+/// Methods for accessing the styled spans of a [`TermString`].
+///
+/// A span is a string with a [`TermStyle`] attached to it. A [`TermString`]
+/// is made of spans, in order.
+///
+/// Spans may be empty. Notably, removing text can leave an empty last
+/// span, holding the style [`append_str()`] inherits.
+///
+/// [`append_str()`]: TermString::append_str
+impl TermString {
+    /// Return an iterator over the spans of [`TermString`].
+    ///
+    /// # Examples
+    ///
+    /// ``` rust
+    /// # use term_string::{TermString, TermStyle};
+    /// let ts = TermString::new(TermStyle::bold(), "bold") + TermString::from(" plain");
+    /// let spans: Vec<_> = ts.spans().collect();
+    /// assert_eq!(spans, [(&TermStyle::bold(), "bold"), (&TermStyle::default(), " plain")]);
+    /// ```
+    pub fn spans<'a>(&'a self) -> impl Iterator<Item = (&'a TermStyle, &'a str)> + 'a {
+        self.elements.iter().map(|e| (&e.style, e.text.as_str()))
+    }
+
+    /// Return an iterator over the spans of [`TermString`], allowing
+    /// modifying their styles and text.
+    ///
+    /// # Examples
+    ///
+    /// ``` rust
+    /// # use term_string::{TermString, TermStyle};
+    /// let mut ts = TermString::new(TermStyle::bold(), "bold") + TermString::from(" plain");
+    ///
+    /// for (style, text) in ts.spans_mut() {
+    ///     if style.has_bold() {
+    ///         style.add_underline(true);
+    ///         *text = text.to_uppercase();
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(ts.to_ansi_string(), "\x1b[1;4mBOLD\x1b[0m plain");
+    /// ```
+    pub fn spans_mut<'a>(&'a mut self) -> impl Iterator<Item = (&'a mut TermStyle, &'a mut String)> + 'a {
+        self.elements.iter_mut().map(|e| (&mut e.style, &mut e.text))
+    }
+
+    /// Return an iterator over the chars of [`TermString`], and their styles.
+    ///
+    /// # Examples
+    ///
+    /// ``` rust
+    /// # use term_string::{TermString, TermStyle};
+    /// let ts = TermString::new(TermStyle::bold(), "a") + TermString::from("b");
+    /// let chars: Vec<_> = ts.styled_chars().collect();
+    /// assert_eq!(chars, [('a', TermStyle::bold()), ('b', TermStyle::default())]);
+    /// ```
+    pub fn styled_chars<'a>(&'a self) -> impl Iterator<Item = (char, TermStyle)> + 'a {
+        self.elements
+            .iter()
+            .flat_map(|e| e.text.chars().map(move |c| (c, e.style)))
+    }
+
+    /// Return the style of the char at byte offset `idx`.
+    ///
+    /// `None` is returned if `idx` is out of bounds, or not on a char boundary.
+    ///
+    /// # Examples
+    ///
+    /// ``` rust
+    /// # use term_string::{TermString, TermStyle};
+    /// let ts = TermString::new(TermStyle::bold(), "é") + TermString::from("a");
+    /// assert_eq!(ts.style_at(0), Some(TermStyle::bold()));
+    /// assert_eq!(ts.style_at(1), None);
+    /// assert_eq!(ts.style_at(2), Some(TermStyle::default()));
+    /// assert_eq!(ts.style_at(3), None);
+    /// ```
+    pub fn style_at(&self, idx: usize) -> Option<TermStyle> {
+        let mut e_start = 0;
+
+        for e in &self.elements {
+            if idx < e_start + e.text.len() {
+                return if e.text.is_char_boundary(idx - e_start) {
+                    Some(e.style)
+                } else {
+                    None
+                };
+            }
+            e_start += e.text.len();
+        }
+
+        None
+    }
+}
+
+/// Build a [`TermString`] from spans.
+///
+/// # Examples
+///
+/// ``` rust
+/// use term_string::{TermString, TermStyle};
+///
+/// let ts = TermString::new(TermStyle::bold(), "bold") + TermString::from(" plain");
+///
+/// // spans can be collected back into a TermString
+/// let copy: TermString = ts.spans().map(|(&style, text)| (style, text)).collect();
+/// assert_eq!(copy.as_string(), "bold plain");
+///
+/// let ts: TermString = vec![(TermStyle::bold(), "a"), (TermStyle::dim(), "b")].into_iter().collect();
+/// assert_eq!(ts.to_ansi_string(), "\x1b[1ma\x1b[0;2mb\x1b[0m");
+/// ```
+impl<S> FromIterator<(TermStyle, S)> for TermString
+where
+    S: Borrow<str>,
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (TermStyle, S)>,
+    {
+        let mut ts = Self::default();
+
+        for (style, text) in iter {
+            ts.append_term_str(Self::new(style, text));
+        }
+
+        ts
+    }
+}
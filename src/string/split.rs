@@ -0,0 +1,172 @@
+/*
+    This file is a part of term-string.
+
+    Copyright (C) 2018 Mohammad AlSaleh <CE.Mohammad.AlSaleh at gmail.com>
+    https://github.com/rust-alt/term-string
+
+    This Source Code Form is subject to the terms of the Mozilla Public
+    License, v. 2.0. If a copy of the MPL was not distributed with this
+    file, You can obtain one at <http://mozilla.org/MPL/2.0/>.
+*/
+
+use super::TermString;
+
+// Byte ranges of the pieces of a text of length len, between the
+// separators at the byte ranges seps.
+fn between<I>(len: usize, seps: I) -> Vec<(usize, usize)>
+where
+    I: Iterator<Item = (usize, usize)>,
+{
+    let mut start = 0;
+    let mut ranges: Vec<_> = seps
+        .map(|(sep_start, sep_end)| {
+            let range = (start, sep_start);
+            start = sep_end;
+            range
+        })
+        .collect();
+
+    ranges.push((start, len));
+    ranges
+}
+
+// Byte ranges of the separators pat in text.
+fn matches<'t>(text: &'t str, pat: &'t str) -> impl Iterator<Item = (usize, usize)> + 't {
+    text.match_indices(pat).map(|(i, m)| (i, i + m.len()))
+}
+
+// Byte ranges of the lines in text, as in str::lines().
+fn line_ranges(text: &str) -> Vec<(usize, usize)> {
+    let mut ranges = between(text.len(), matches(text, "\n"));
+
+    // A final line ending is optional
+    if ranges.last() == Some(&(text.len(), text.len())) {
+        ranges.pop();
+    }
+
+    // Only lines ended with \r\n lose their \r
+    for range in &mut ranges {
+        if range.1 < text.len() && text[range.0..range.1].ends_with('\r') {
+            range.1 -= 1;
+        }
+    }
+
+    ranges
+}
+
+// Byte ranges of the non-whitespace runs in text.
+fn word_ranges(text: &str) -> Vec<(usize, usize)> {
+    let mut ranges = Vec::new();
+    let mut start = None;
+
+    for (i, c) in text.char_indices() {
+        match (c.is_whitespace(), start) {
+            (true, Some(s)) => {
+                ranges.push((s, i));
+                start = None;
+            },
+            (false, None) => start = Some(i),
+            _ => (),
+        }
+    }
+
+    if let Some(s) = start {
+        ranges.push((s, text.len()));
+    }
+
+    ranges
+}
+
+/// Methods for splitting [`TermString`] into pieces.
+///
+/// These work like their [`str`] counterparts on the un-styled string,
+/// but yield [`TermString`] pieces keeping the styles of their text,
+/// even if a piece spans multiple styles.
+///
+/// # Examples
+///
+/// Prefixing each line with a gutter:
+///
+/// ``` rust
+/// use term_string::{color, TermString, TermStyle};
+///
+/// let ts = TermString::new(TermStyle::bold(), "error: ") + TermString::from("first\nsecond");
+/// let gutter = TermString::new(TermStyle::fg(color::BLUE), "| ");
+///
+/// for line in ts.lines() {
+///     (gutter.clone() + line).println();
+/// }
+/// ```
+impl TermString {
+    fn _pieces<'a>(&'a self, ranges: Vec<(usize, usize)>) -> impl Iterator<Item = Self> + 'a {
+        ranges.into_iter().map(move |(start, end)| self._slice(start, end))
+    }
+
+    /// Return an iterator over the lines of [`TermString`].
+    ///
+    /// Lines are ended with `\n` or `\r\n`, which are not included in
+    /// the yielded lines. A final line ending is optional.
+    ///
+    /// # Examples
+    ///
+    /// ``` rust
+    /// # use term_string::{TermString, TermStyle};
+    /// let ts = TermString::new(TermStyle::bold(), "one\ntw") + TermString::from("o\r\n");
+    /// let lines: Vec<_> = ts.lines().map(|l| l.as_string()).collect();
+    /// assert_eq!(lines, ["one", "two"]);
+    /// ```
+    pub fn lines<'a>(&'a self) -> impl Iterator<Item = Self> + 'a {
+        let text = self.as_string();
+        self._pieces(line_ranges(&text))
+    }
+
+    /// Return an iterator over the pieces of [`TermString`] separated
+    /// by `pat`.
+    ///
+    /// # Examples
+    ///
+    /// ``` rust
+    /// # use term_string::{TermString, TermStyle};
+    /// let ts = TermString::new(TermStyle::bold(), "a, b") + TermString::from(", c");
+    /// let pieces: Vec<_> = ts.split(", ").map(|p| p.as_string()).collect();
+    /// assert_eq!(pieces, ["a", "b", "c"]);
+    /// ```
+    pub fn split<'a>(&'a self, pat: &str) -> impl Iterator<Item = Self> + 'a {
+        let text = self.as_string();
+        self._pieces(between(text.len(), matches(&text, pat)))
+    }
+
+    /// Return an iterator over at most `n` pieces of [`TermString`]
+    /// separated by `pat`. The last piece contains the rest of [`TermString`].
+    ///
+    /// # Examples
+    ///
+    /// ``` rust
+    /// # use term_string::{TermString, TermStyle};
+    /// let ts = TermString::new(TermStyle::bold(), "key") + TermString::from("=a=b");
+    /// let pieces: Vec<_> = ts.splitn(2, "=").map(|p| p.as_string()).collect();
+    /// assert_eq!(pieces, ["key", "a=b"]);
+    /// ```
+    pub fn splitn<'a>(&'a self, n: usize, pat: &str) -> impl Iterator<Item = Self> + 'a {
+        let text = self.as_string();
+        let seps = matches(&text, pat).take(n.saturating_sub(1));
+        let ranges = if n == 0 { Vec::new() } else { between(text.len(), seps) };
+        self._pieces(ranges)
+    }
+
+    /// Return an iterator over the pieces of [`TermString`] separated
+    /// by any amount of whitespace.
+    ///
+    /// # Examples
+    ///
+    /// ``` rust
+    /// # use term_string::{TermString, TermStyle};
+    /// let ts = TermString::new(TermStyle::bold(), " some  bold") + TermString::from("\ttext ");
+    /// let words: Vec<_> = ts.split_whitespace().map(|w| w.as_string()).collect();
+    /// assert_eq!(words, ["some", "bold", "text"]);
+    /// ```
+    pub fn split_whitespace<'a>(&'a self) -> impl Iterator<Item = Self> + 'a {
+        let text = self.as_string();
+        self._pieces(word_ranges(&text))
+    }
+}
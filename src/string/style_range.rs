@@ -0,0 +1,170 @@
+/*
+    This file is a part of term-string.
+
+    Copyright (C) 2018 Mohammad AlSaleh <CE.Mohammad.AlSaleh at gmail.com>
+    https://github.com/rust-alt/term-string
+
+    This Source Code Form is subject to the terms of the Mozilla Public
+    License, v. 2.0. If a copy of the MPL was not distributed with this
+    file, You can obtain one at <http://mozilla.org/MPL/2.0/>.
+*/
+
+use std::ops::RangeBounds;
+
+use style::TermStyle;
+
+use super::{TermString, TermStringElement};
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+/// How a [`TermStyle`] is merged into the styles of a [`TermString`] range,
+/// with [`TermString::style_range()`].
+///
+/// [`TermString::style_range()`]: TermString::style_range
+pub enum MergeMode {
+    /// Overlay the style, replacing attributes of the same variant.
+    /// Check out [`TermStyle::add_style()`].
+    ///
+    /// [`TermStyle::add_style()`]: TermStyle::add_style
+    Add,
+    /// Fill in the style, only setting attributes of variants not already set.
+    /// Check out [`TermStyle::or_style()`].
+    ///
+    /// [`TermStyle::or_style()`]: TermStyle::or_style
+    Or,
+}
+
+/// Methods for styling a range of a [`TermString`].
+impl TermString {
+    // Merge styles into the text in byte ranges, in one pass over the
+    // elements. Ranges must be sorted, not overlapping, and on char
+    // boundaries.
+    pub(crate) fn _style_ranges<I>(&mut self, ranges: I, mode: MergeMode)
+    where
+        I: IntoIterator<Item = (usize, usize, TermStyle)>,
+    {
+        let mut ranges = ranges.into_iter().filter(|r| r.0 < r.1).peekable();
+        let mut elements = Vec::with_capacity(self.elements.len());
+        let mut e_start = 0;
+
+        for e in &self.elements {
+            let e_end = e_start + e.text.len();
+            let mut pos = e_start;
+
+            // Split at range boundaries, then merge styles into the parts in ranges
+            while let Some(&(start, end, style)) = ranges.peek() {
+                if start >= e_end {
+                    break;
+                }
+
+                let (s, t) = (start.max(pos), end.min(e_end));
+                let mut styled = e.style;
+                match mode {
+                    MergeMode::Add => styled.add_style(style),
+                    MergeMode::Or => styled.or_style(style),
+                }
+
+                for &(style, from, to) in &[(e.style, pos, s), (styled, s, t)] {
+                    if from < to {
+                        let text = &e.text[from - e_start..to - e_start];
+                        elements.push(TermStringElement::new(style, text));
+                    }
+                }
+
+                pos = t;
+                if end > e_end {
+                    break;
+                }
+                ranges.next();
+            }
+
+            // Empty elements are left for _normalize() to handle
+            if pos < e_end || e.text.is_empty() {
+                elements.push(TermStringElement::new(e.style, &e.text[pos - e_start..]));
+            }
+
+            e_start = e_end;
+        }
+
+        self.elements = elements;
+        self._normalize();
+    }
+
+    /// Merge `style` into the styles of the text in a byte range of
+    /// [`TermString`], with `mode`.
+    ///
+    /// Text outside the range keeps its style.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds, or not on char boundaries.
+    ///
+    /// # Examples
+    ///
+    /// ``` rust
+    /// use term_string::{color, MergeMode, TermString, TermStyle};
+    ///
+    /// let keyword = TermStyle::fg(color::BLUE);
+    /// let mut ts = TermString::new(keyword, "fn") + TermString::from(" main()");
+    ///
+    /// // mark an error, keeping syntax colors
+    /// ts.style_range(1..4, TermStyle::underline(true), MergeMode::Add);
+    /// assert_eq!(ts.to_ansi_string(), "\x1b[34mf\x1b[4mn\x1b[39m m\x1b[0main()");
+    /// ```
+    pub fn style_range<R, IT>(&mut self, range: R, style: IT, mode: MergeMode)
+    where
+        R: RangeBounds<usize>,
+        IT: Into<TermStyle>,
+    {
+        let (start, end) = self._checked_range(&range);
+        self._style_ranges(Some((start, end, style.into())), mode);
+    }
+
+    /// Merge `style` into the styles of the text in a char range of
+    /// [`TermString`], with `mode`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ``` rust
+    /// use term_string::{color, MergeMode, TermString, TermStyle};
+    ///
+    /// let mut ts = TermString::new(TermStyle::bg(color::RED), "日本") + TermString::from("語");
+    ///
+    /// // red stays the background of "本"
+    /// ts.style_char_range(1.., TermStyle::bg(color::BLUE), MergeMode::Or);
+    /// assert_eq!(ts.to_ansi_string(), "\x1b[41m日本\x1b[44m語\x1b[0m");
+    /// ```
+    pub fn style_char_range<R, IT>(&mut self, range: R, style: IT, mode: MergeMode)
+    where
+        R: RangeBounds<usize>,
+        IT: Into<TermStyle>,
+    {
+        let (start, end) = self._checked_char_range(&range);
+        self._style_ranges(Some((start, end, style.into())), mode);
+    }
+
+    chaining_fn!(TermString, style_range,
+                 pub fn with_styled_range<R, IT>(mut self, range: R, style: IT, mode: MergeMode) -> Self
+                 where
+                     R: RangeBounds<usize>,
+                     IT: Into<TermStyle>,
+                 {
+                     self.style_range(range, style, mode);
+                     self
+                 }
+    );
+
+    chaining_fn!(TermString, style_char_range,
+                 pub fn with_styled_char_range<R, IT>(mut self, range: R, style: IT, mode: MergeMode) -> Self
+                 where
+                     R: RangeBounds<usize>,
+                     IT: Into<TermStyle>,
+                 {
+                     self.style_char_range(range, style, mode);
+                     self
+                 }
+    );
+}
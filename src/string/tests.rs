@@ -9,9 +9,25 @@
     file, You can obtain one at <http://mozilla.org/MPL/2.0/>.
 */
 
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::ops::Bound;
+
+use choice::ColorChoice;
+use string::{MergeMode, SanitizeMode, Sanitizer, TermStringElement};
 use string::TermString as Str;
+use string::{TermInfo, TerminfoTerminal};
+use term::Terminal;
+use style::color::{BLUE, RED};
+use style::ColorDepth;
 use style::TermStyle as Sty;
 
+// The text and style of each element
+fn elements(ts: &Str) -> Vec<(&str, Sty)> {
+    ts.elements.iter().map(|e| (e.text.as_str(), e.style)).collect()
+}
+
 // Essentials
 
 #[test]
@@ -161,3 +177,1190 @@ fn with_or_style() {}
 
 #[test]
 fn with_style() {}
+
+// ANSI
+
+#[test]
+fn from_ansi() {
+    use string::ControlPolicy;
+    use style::color::{BLUE, RED};
+
+    let t_str = Str::from_ansi("\x1b[1;31merror\x1b[0m: \x1b[2K\x1b[44mfailed\x1b[m");
+    assert_eq!(t_str.as_string(), "error: failed");
+    assert!(t_str.elements.len() == 3);
+    assert!(t_str.elements[0].style == Sty::bold() | Sty::fg(RED));
+    assert!(t_str.elements[1].style == Sty::default());
+    assert!(t_str.elements[2].style == Sty::bg(BLUE));
+
+    // Styles carry over non-SGR sequences, unknown codes are skipped
+    let text = "\x1b[4mun\x1b[?25lder\x1b[53mline\x1b]8;;http://x\x1b\\d\x1b(B";
+    let t_str = Str::from_ansi(text);
+    assert!(t_str.elements.len() == 1);
+    assert_eq!(t_str.as_string(), "underlined");
+    assert!(t_str.elements[0].style == Sty::underline(true));
+
+    let t_str = Str::from_ansi_with(text, ControlPolicy::Preserve);
+    assert_eq!(t_str.as_string(), "un\x1b[?25lderline\x1b]8;;http://x\x1b\\d\x1b(B");
+    assert!(t_str.elements[0].style == Sty::underline(true));
+
+    // Off codes unset attrs, so the text after them is plain
+    let t_str = Str::from_ansi("\x1b[4mA\x1b[24mB\x1b[3mC\x1b[23mD");
+    assert!(t_str.elements[1].style == Sty::default());
+    assert!(t_str.elements[3].style == Sty::default());
+    let expected = Str::new(Sty::underline(true), "A")
+        + Str::from("B")
+        + Str::new(Sty::italic(true), "C")
+        + Str::from("D");
+    assert!(t_str == expected);
+    let ansi = "\x1b[4mA\x1b[0mB\x1b[3mC\x1b[0mD";
+    assert_eq!(t_str.to_ansi_string(), ansi);
+    assert!(Str::from_ansi(ansi) == t_str);
+
+    // Incomplete sequences
+    assert_eq!(Str::from_ansi("abc\x1b[1").as_string(), "abc");
+    assert_eq!(Str::from_ansi("abc\x1b").as_string(), "abc");
+}
+
+#[test]
+fn to_ansi_string() {
+    use style::color::RED;
+
+    let mut t_str = Str::new(Sty::bold() | Sty::fg(RED), "error");
+    t_str += Str::new(Sty::bold(), "");
+    t_str += Str::from(": ");
+    t_str += Str::new(Sty::underline(true) | Sty::bg_rgb(1, 2, 3), "failed");
+
+    let ansi = "\x1b[1;31merror\x1b[0m: \x1b[4;48;2;1;2;3mfailed\x1b[0m";
+    assert_eq!(t_str.to_ansi_string(), ansi);
+    assert_eq!(t_str.write_ansi_ret_out(Vec::new()), ansi.as_bytes());
+    assert_eq!(Str::default().to_ansi_string(), "");
+
+    // Round-trip through from_ansi()
+    assert_eq!(Str::from_ansi(ansi).to_ansi_string(), ansi);
+}
+
+#[test]
+fn to_ansi_string_transitions() {
+    use style::color::{BLUE, RED};
+
+    // Only changed attrs are written between elements
+    let t_str = Str::new(Sty::bold(), "a")
+        + Str::new(Sty::bold() | Sty::fg(RED), "b")
+        + Str::new(Sty::fg(RED), "c");
+    assert_eq!(t_str.to_ansi_string(), "\x1b[1ma\x1b[31mb\x1b[22mc\x1b[0m");
+
+    // Turning off one of bold and dim turns on the other again
+    let t_str = Str::new(Sty::bold() | Sty::dim() | Sty::bg(BLUE), "a")
+        + Str::new(Sty::dim() | Sty::bg(BLUE), "b");
+    assert_eq!(t_str.to_ansi_string(), "\x1b[1;2;44ma\x1b[22;2mb\x1b[0m");
+
+    // A reset is used if shorter, and no reset is needed after plain text
+    let t_str = Str::new(Sty::underline(true) | Sty::fg(RED), "a")
+        + Str::new(Sty::bold(), "b")
+        + Str::from("c");
+    assert_eq!(t_str.to_ansi_string(), "\x1b[4;31ma\x1b[0;1mb\x1b[0mc");
+
+    // Round-trip through from_ansi()
+    let ansi = t_str.to_ansi_string();
+    assert_eq!(Str::from_ansi(&ansi).to_ansi_string(), ansi);
+
+    // Attrs with no visible effect are not written
+    let t_str = Str::new(Sty::italic(false), "a") + Str::new(Sty::bold(), "b");
+    assert_eq!(t_str.to_ansi_string(), "a\x1b[1mb\x1b[0m");
+}
+
+// Terminfo
+
+// An xterm-like terminfo, with the off codes for italic, underline and
+// standout only if off_caps is true.
+fn terminfo(off_caps: bool) -> TermInfo {
+    let mut strings: HashMap<_, _> = vec![
+        ("sgr0", "\x1b[0m"),
+        ("bold", "\x1b[1m"),
+        ("dim", "\x1b[2m"),
+        ("sitm", "\x1b[3m"),
+        ("smul", "\x1b[4m"),
+        ("rev", "\x1b[7m"),
+        ("smso", "\x1b[7m"),
+        ("setaf", "\x1b[3%p1%dm"),
+        ("setab", "\x1b[4%p1%dm"),
+    ]
+    .into_iter()
+    .map(|(cap, s)| (cap, s.as_bytes().to_vec()))
+    .collect();
+
+    if off_caps {
+        strings.insert("ritm", b"\x1b[23m".to_vec());
+        strings.insert("rmul", b"\x1b[24m".to_vec());
+        strings.insert("rmso", b"\x1b[27m".to_vec());
+    }
+
+    TermInfo {
+        names: vec!["test".into()],
+        bools: HashMap::new(),
+        numbers: vec![("colors", 8)].into_iter().collect(),
+        strings,
+    }
+}
+
+fn write_terminfo(ts: &Str, off_caps: bool, depth: ColorDepth) -> String {
+    let mut out_term = TerminfoTerminal::new_with_terminfo(Vec::new(), terminfo(off_caps));
+    ts._write_elements(&mut out_term, depth);
+    String::from_utf8(out_term.into_inner()).unwrap()
+}
+
+#[test]
+fn terminfo_transitions_add() {
+    // Only added attrs are written, after an initial reset
+    let ts = Str::new(Sty::bold(), "a")
+        + Str::new(Sty::bold() | Sty::fg(RED), "b")
+        + Str::new(Sty::bold() | Sty::fg(RED) | Sty::underline(true), "c");
+    let expected = "\x1b[0m\x1b[1ma\x1b[31mb\x1b[4mc\x1b[0m";
+    assert_eq!(write_terminfo(&ts, true, ColorDepth::Ansi8), expected);
+    assert_eq!(write_terminfo(&ts, false, ColorDepth::Ansi8), expected);
+
+    // Colors are replaced without a reset
+    let ts = Str::new(Sty::fg(RED) | Sty::bg(BLUE), "a") + Str::new(Sty::fg(BLUE) | Sty::bg(BLUE), "b");
+    let expected = "\x1b[0m\x1b[31m\x1b[44ma\x1b[34mb\x1b[0m";
+    assert_eq!(write_terminfo(&ts, false, ColorDepth::Ansi8), expected);
+
+    // Empty elements, and text with no style
+    let ts = Str::from("a") + Str::new(Sty::bold(), "") + Str::from("b");
+    assert_eq!(write_terminfo(&ts, true, ColorDepth::Ansi8), "\x1b[0mab");
+}
+
+#[test]
+fn terminfo_transitions_off() {
+    // Italic, underline and standout are turned off with their off codes
+    let ts = Str::new(Sty::italic(true) | Sty::underline(true) | Sty::fg(RED), "a")
+        + Str::new(Sty::underline(true) | Sty::fg(RED), "b")
+        + Str::new(Sty::standout(true) | Sty::fg(RED), "c")
+        + Str::new(Sty::fg(RED), "d");
+    assert_eq!(
+        write_terminfo(&ts, true, ColorDepth::Ansi8),
+        "\x1b[0m\x1b[3m\x1b[4m\x1b[31ma\x1b[23mb\x1b[24m\x1b[7mc\x1b[27md\x1b[0m"
+    );
+
+    // Turning them off is a no-op if they are off already
+    let ts = Str::new(Sty::italic(false), "a") + Str::from("b");
+    assert_eq!(write_terminfo(&ts, true, ColorDepth::Ansi8), "\x1b[0m\x1b[23mab");
+}
+
+#[test]
+fn terminfo_transitions_reset() {
+    // No off codes in terminfo
+    let ts = Str::new(Sty::underline(true) | Sty::fg(RED), "a") + Str::new(Sty::fg(RED), "b");
+    assert_eq!(
+        write_terminfo(&ts, false, ColorDepth::Ansi8),
+        "\x1b[0m\x1b[4m\x1b[31ma\x1b[0m\x1b[31mb\x1b[0m"
+    );
+
+    // An attr not written for lack of support still forces a reset when removed
+    let ts = Str::new(Sty::standout(true) | Sty::italic(true), "a") + Str::from("b");
+    assert_eq!(write_terminfo(&ts, false, ColorDepth::Ansi8), "\x1b[0m\x1b[3m\x1b[7ma\x1b[0mb");
+
+    // No off codes in SGR
+    for &attr in &[Sty::bold(), Sty::dim()] {
+        let ts = Str::new(attr | Sty::fg(RED), "a") + Str::new(Sty::fg(RED), "b");
+        let on = if attr == Sty::bold() { "\x1b[1m" } else { "\x1b[2m" };
+        let expected = format!("\x1b[0m{}\x1b[31ma\x1b[0m\x1b[31mb\x1b[0m", on);
+        assert_eq!(write_terminfo(&ts, true, ColorDepth::Ansi8), expected);
+    }
+
+    // Turning off standout may turn off reverse too
+    let ts = Str::new(Sty::standout(true), "a") + Str::new(Sty::reverse(), "b");
+    assert_eq!(write_terminfo(&ts, true, ColorDepth::Ansi8), "\x1b[0m\x1b[7ma\x1b[0m\x1b[7mb\x1b[0m");
+    let ts = Str::new(Sty::reverse() | Sty::standout(true), "a") + Str::new(Sty::reverse(), "b");
+    assert_eq!(
+        write_terminfo(&ts, true, ColorDepth::Ansi8),
+        "\x1b[0m\x1b[7m\x1b[7ma\x1b[0m\x1b[7mb\x1b[0m"
+    );
+
+    // Removed colors
+    let ts = Str::new(Sty::bold() | Sty::bg(BLUE), "a") + Str::new(Sty::bold(), "b");
+    assert_eq!(
+        write_terminfo(&ts, true, ColorDepth::Ansi8),
+        "\x1b[0m\x1b[1m\x1b[44ma\x1b[0m\x1b[1mb\x1b[0m"
+    );
+
+    // Rgb colors are replaced, or removed with a reset
+    let ts = Str::new(Sty::fg_rgb(1, 2, 3), "a") + Str::new(Sty::fg_rgb(4, 5, 6), "b") + Str::from("c");
+    assert_eq!(
+        write_terminfo(&ts, true, ColorDepth::TrueColor),
+        "\x1b[0m\x1b[38;2;1;2;3ma\x1b[38;2;4;5;6mb\x1b[0mc"
+    );
+}
+
+// Sets the global ColorChoice, and restores the previous one on drop.
+// No other test depends on the global choice.
+struct GlobalChoice(ColorChoice);
+
+impl GlobalChoice {
+    fn set(choice: ColorChoice) -> Self {
+        let prev = ColorChoice::global();
+        ColorChoice::set_global(choice);
+        GlobalChoice(prev)
+    }
+}
+
+impl Drop for GlobalChoice {
+    fn drop(&mut self) {
+        ColorChoice::set_global(self.0);
+    }
+}
+
+#[test]
+fn display() {
+    let t_str = Str::new(Sty::bold(), "bold") + Str::from(" plain");
+
+    {
+        let _guard = GlobalChoice::set(ColorChoice::Always);
+        assert_eq!(format!("{}", t_str), t_str.to_ansi_string());
+        assert_eq!(format!("{:#}", t_str), "bold plain");
+        assert_eq!(t_str.to_string(), "\x1b[1mbold\x1b[0m plain");
+
+        // padded by display width, the padding inheriting adjacent backgrounds
+        let ts = Str::new(Sty::bg(RED), "日本");
+        assert_eq!(format!("{:>6}", ts), "\x1b[41m  日本\x1b[0m");
+        assert_eq!(format!("{:*<5}", ts), "\x1b[41m日本*\x1b[0m");
+        assert_eq!(format!("{:^7}", ts), "\x1b[41m 日本  \x1b[0m");
+        assert_eq!(format!("{:^#7}", ts), " 日本  ");
+        assert_eq!(format!("{:2}", ts), "\x1b[41m日本\x1b[0m");
+    }
+
+    {
+        let _guard = GlobalChoice::set(ColorChoice::Never);
+        assert_eq!(format!("{}", t_str), "bold plain");
+        assert_eq!(format!("{:#}", t_str), "bold plain");
+    }
+
+    // Auto doesn't assume a tty
+    let auto = if ColorChoice::Auto.should_style(false) {
+        t_str.to_ansi_string()
+    } else {
+        t_str.as_string()
+    };
+    assert_eq!(format!("{}", t_str), auto);
+}
+
+// Width
+
+#[test]
+fn width() {
+    // ascii, latin with a combining mark, CJK
+    let ts = Str::new(Sty::bold(), "ae\u{301}") + Str::new(Sty::dim(), "日本");
+    assert_eq!(ts.len(), 10);
+    assert_eq!(ts.width(), 6);
+
+    // emoji: presentation selector, ZWJ sequence, flag, skin tone
+    assert_eq!(Str::from("\u{2764}\u{fe0f}").width(), 2);
+    assert_eq!(Str::from("\u{1f469}\u{200d}\u{1f4bb}").width(), 2);
+    assert_eq!(Str::from("\u{1f1f8}\u{1f1e6}").width(), 2);
+    assert_eq!(Str::from("\u{1f44d}\u{1f3fd}").width(), 2);
+
+    // a cluster spanning elements is measured once
+    let ts = Str::new(Sty::bold(), "e") + Str::new(Sty::dim(), "\u{301}");
+    assert_eq!(ts.width(), 1);
+
+    assert_eq!(Str::from("\t\x07").width(), 0);
+    assert_eq!(Str::default().width(), 0);
+}
+
+#[test]
+fn char_count() {
+    let ts = Str::new(Sty::bold(), "ae\u{301}") + Str::new(Sty::dim(), "日本");
+    assert_eq!(ts.char_count(), 5);
+    assert_eq!(Str::default().char_count(), 0);
+}
+
+#[test]
+fn truncate_to_width() {
+    let mut ts = Str::new(Sty::bold(), "bold") + Str::new(Sty::dim(), " dim");
+    ts.truncate_to_width(6, "~");
+    assert_eq!(ts.as_string(), "bold ~");
+    assert_eq!(ts.elements.len(), 3);
+    assert_eq!(ts.elements[0].style, Sty::bold());
+    assert_eq!(ts.elements[1].style, Sty::dim());
+    assert_eq!(ts.elements[2].style, Sty::default());
+
+    // fits, so nothing changes
+    let mut ts = Str::from("fits");
+    ts.truncate_to_width(4, "...");
+    assert_eq!(ts.as_string(), "fits");
+
+    // a wide char that doesn't fit is dropped whole
+    let mut ts = Str::from("a日本");
+    ts.truncate_to_width(4, "~");
+    assert_eq!(ts.as_string(), "a日~");
+    let mut ts = Str::from("a日本");
+    ts.truncate_to_width(3, "~");
+    assert_eq!(ts.as_string(), "a~");
+
+    // combining marks stay with their base
+    let mut ts = Str::from("ae\u{301}e\u{301}");
+    ts.truncate_to_width(2, "");
+    assert_eq!(ts.as_string(), "ae\u{301}");
+
+    // the ellipsis is truncated if it doesn't fit
+    let mut ts = Str::from("abcdef");
+    ts.truncate_to_width(2, Str::new(Sty::bold(), "..."));
+    assert_eq!(ts.as_string(), "..");
+    assert_eq!(ts.elements[0].style, Sty::bold());
+}
+
+#[test]
+fn truncate_start_to_width() {
+    let mut ts = Str::new(Sty::bold(), "src/") + Str::new(Sty::dim(), "mod.rs");
+    ts.truncate_start_to_width(8, Str::new(Sty::underline(true), "…"));
+    assert_eq!(ts.as_string(), "…/mod.rs");
+    assert_eq!(ts.width(), 8);
+    assert_eq!(ts.elements.len(), 3);
+    assert_eq!(ts.elements[0].style, Sty::underline(true));
+    assert_eq!(ts.elements[1].style, Sty::bold());
+    assert_eq!(ts.elements[2].style, Sty::dim());
+
+    let mut ts = Str::from("日本語");
+    ts.truncate_start_to_width(5, "…");
+    assert_eq!(ts.as_string(), "…本語");
+}
+
+#[test]
+fn truncate_middle_to_width() {
+    let mut ts = Str::from("abcdefgh");
+    ts.truncate_middle_to_width(6, "..");
+    assert_eq!(ts.as_string(), "ab..gh");
+    ts = Str::from("abcdefgh").with_middle_truncated_to_width(7, "..");
+    assert_eq!(ts.as_string(), "abc..gh");
+
+    // a column left by a wide char at the start goes to the end
+    let mut ts = Str::from("日本語abc");
+    ts.truncate_middle_to_width(6, "~");
+    assert_eq!(ts.as_string(), "日~abc");
+}
+
+// Wrap
+
+#[test]
+fn wrap() {
+    let ts = Str::new(Sty::bold(), "some bold") + Str::new(Sty::dim(), " and dim text");
+    let lines = ts.wrap(10);
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[0].as_string(), "some bold");
+    assert_eq!(lines[0].elements.len(), 1);
+    assert_eq!(lines[0].elements[0].style, Sty::bold());
+    assert_eq!(lines[1].as_string(), "and dim");
+    assert_eq!(lines[1].elements[0].style, Sty::dim());
+    assert_eq!(lines[2].as_string(), "text");
+
+    // a style change in the middle of a word
+    let ts = Str::new(Sty::bold(), "ab") + Str::new(Sty::dim(), "cd ef");
+    let lines = ts.wrap(4);
+    assert_eq!(lines[0].as_string(), "abcd");
+    assert_eq!(lines[0].elements.len(), 2);
+    assert_eq!(lines[1].as_string(), "ef");
+
+    // explicit newlines and leading whitespace are kept
+    let lines = Str::from("  one two\n\nthree").wrap(7);
+    let lines: Vec<_> = lines.iter().map(|l| l.as_string()).collect();
+    assert_eq!(lines, ["  one", "two", "", "three"]);
+
+    // widths are display widths
+    let lines = Str::from("日本 語").wrap(4);
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0].as_string(), "日本");
+}
+
+#[test]
+fn wrap_hard_break() {
+    let lines = Str::from("a abcdefgh b").wrap(3);
+    let lines: Vec<_> = lines.iter().map(|l| l.as_string()).collect();
+    assert_eq!(lines, ["a", "abc", "def", "gh", "b"]);
+
+    // at least one grapheme goes in a line
+    let lines = Str::from("日本").wrap(1);
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[1].as_string(), "本");
+}
+
+#[test]
+fn wrap_indented() {
+    let ts = Str::from("one two three");
+    let lines = ts.wrap_indented(8, Str::new(Sty::bold(), "- "), "  ");
+    let strs: Vec<_> = lines.iter().map(|l| l.as_string()).collect();
+    assert_eq!(strs, ["- one", "  two", "  three"]);
+    assert_eq!(lines[0].elements[0].style, Sty::bold());
+    assert_eq!(lines[1].elements.len(), 1);
+}
+
+#[test]
+fn fill() {
+    let ts = Str::new(Sty::bold(), "some bold") + " text";
+    let filled = ts.fill(9);
+    assert_eq!(filled.as_string(), "some bold\ntext");
+    assert_eq!(filled.elements[0].style, Sty::bold());
+    assert_eq!(Str::from("a b").fill_indented(3, "> ", "> ").as_string(), "> a\n> b");
+}
+
+// Padding
+
+#[test]
+fn pad_left() {
+    let mut ts = Str::new(Sty::bold(), "ab");
+    ts.pad_left(5, Str::new(Sty::dim(), "."));
+    assert_eq!(ts.as_string(), "...ab");
+    assert_eq!(ts.elements.len(), 2);
+    assert_eq!(ts.elements[0].style, Sty::dim());
+    assert_eq!(ts.elements[1].style, Sty::bold());
+
+    // already wide enough
+    let mut ts = Str::from("abc");
+    ts.pad_left(2, " ");
+    assert_eq!(ts.as_string(), "abc");
+
+    // widths are display widths
+    let mut ts = Str::from("日本");
+    ts.pad_left(6, " ");
+    assert_eq!(ts.as_string(), "  日本");
+}
+
+#[test]
+fn pad_right() {
+    // a multi-column fill is truncated at the end
+    let mut ts = Str::from("a");
+    ts.pad_right(6, "-=");
+    assert_eq!(ts.as_string(), "a-=-=-");
+
+    // wide fill that doesn't fit whole, the leftover column is a space
+    let mut ts = Str::from("a");
+    ts.pad_right(4, "日");
+    assert_eq!(ts.as_string(), "a日 ");
+
+    // the space keeps the fill's background
+    let mut ts = Str::from("a");
+    ts.pad_right(4, Str::new(Sty::bg(BLUE) + Sty::bold(), "日"));
+    assert_eq!(ts.elements.len(), 3);
+    assert_eq!(ts.elements[2].text, " ");
+    assert_eq!(ts.elements[2].style, Sty::bg(BLUE));
+
+    // dotted leaders, as in the docs
+    let mut ts = Str::from("Chapter 1 ");
+    ts.pad_right(16, Str::new(Sty::dim(), "."));
+    ts += Str::from(" 7");
+    assert_eq!(ts.as_string(), "Chapter 1 ...... 7");
+    assert_eq!(ts.elements.len(), 3);
+    assert_eq!(ts.elements[1].text, "......");
+    assert_eq!(ts.elements[1].style, Sty::dim());
+    assert_eq!(ts.elements[2].style, Sty::default());
+}
+
+#[test]
+fn pad_inherit_bg() {
+    let bg = Sty::bg(RED);
+    let mut ts = Str::new(bg + Sty::bold(), "ab");
+    ts.pad_right(4, " ");
+    assert_eq!(ts.elements.len(), 2);
+    assert_eq!(ts.elements[1].style, bg);
+
+    // a fill with its own background keeps it
+    let mut ts = Str::new(bg, "ab");
+    ts.pad_left(4, Str::new(Sty::bg(BLUE), " "));
+    assert_eq!(ts.elements[0].style, Sty::bg(BLUE));
+}
+
+#[test]
+fn center() {
+    let bg = Sty::bg(RED);
+    let ts = Str::new(Sty::bold(), "ab") + Str::new(bg, "c");
+    let ts = ts.with_centered(8, " ");
+    assert_eq!(ts.as_string(), "  abc   ");
+    assert_eq!(ts.elements.len(), 3);
+    assert_eq!(ts.elements[0].style, Sty::default());
+    assert_eq!(ts.elements[2].style, bg);
+}
+
+// Slicing
+
+#[test]
+fn slice() {
+    let ts = Str::new(Sty::bold(), "bold") + Str::new(Sty::dim(), " dim");
+    let part = ts.slice(2..6);
+    assert_eq!(part.as_string(), "ld d");
+    assert_eq!(part.elements.len(), 2);
+    assert_eq!(part.elements[0].style, Sty::bold());
+    assert_eq!(part.elements[1].style, Sty::dim());
+
+    let part = ts.slice(5..);
+    assert_eq!(part.elements.len(), 1);
+    assert_eq!(part.elements[0].text, "dim");
+
+    assert_eq!(ts.slice(..=3).as_string(), "bold");
+    assert_eq!(ts.slice(..).as_string(), "bold dim");
+    assert!(ts.slice(3..3).is_empty());
+}
+
+#[test]
+#[should_panic]
+fn slice_not_char_boundary() {
+    Str::from("é").slice(1..);
+}
+
+#[test]
+fn get() {
+    let ts = Str::new(Sty::bold(), "é") + Str::new(Sty::dim(), "a");
+    assert_eq!(ts.get(0..2).unwrap().as_string(), "é");
+    assert_eq!(ts.get(2..3).unwrap().elements[0].style, Sty::dim());
+    assert!(ts.get(1..3).is_none());
+    assert!(ts.get(0..4).is_none());
+    assert!(ts.get((Bound::Included(2), Bound::Excluded(1))).is_none());
+    assert!(ts.get(3..).unwrap().is_empty());
+}
+
+#[test]
+fn char_slice() {
+    let ts = Str::new(Sty::bold(), "é") + Str::new(Sty::dim(), "ab");
+    assert_eq!(ts.char_slice(1..2).as_string(), "a");
+    assert_eq!(ts.char_slice(..2).as_string(), "éa");
+    assert_eq!(ts.char_slice(3..).as_string(), "");
+}
+
+#[test]
+fn char_get() {
+    let ts = Str::new(Sty::bold(), "é") + Str::new(Sty::dim(), "ab");
+    assert_eq!(ts.char_get(1..2).unwrap().as_string(), "a");
+    assert_eq!(ts.char_get(..2).unwrap().elements[0].style, Sty::bold());
+    assert!(ts.char_get(3..).unwrap().is_empty());
+    assert!(ts.char_get(..4).is_none());
+    assert!(ts.char_get(4..).is_none());
+    assert!(ts.char_get((Bound::Included(2), Bound::Excluded(1))).is_none());
+}
+
+#[test]
+#[should_panic]
+fn char_slice_out_of_bounds() {
+    Str::from("é").char_slice(..2);
+}
+
+#[test]
+fn split_at() {
+    let ts = Str::new(Sty::bold(), "ab") + Str::new(Sty::dim(), "cd");
+    let (left, right) = ts.split_at(3);
+    assert_eq!(left.as_string(), "abc");
+    assert_eq!(left.elements.len(), 2);
+    assert_eq!(right.as_string(), "d");
+    assert_eq!(right.elements[0].style, Sty::dim());
+}
+
+#[test]
+fn split_at_checked() {
+    let ts = Str::new(Sty::bold(), "é") + Str::new(Sty::dim(), "cd");
+    let (left, right) = ts.split_at_checked(3).unwrap();
+    assert_eq!(left.as_string(), "éc");
+    assert_eq!(left.elements.len(), 2);
+    assert_eq!(right.as_string(), "d");
+    assert_eq!(right.elements[0].style, Sty::dim());
+
+    let (left, right) = ts.split_at_checked(4).unwrap();
+    assert_eq!(left.as_string(), "écd");
+    assert!(right.is_empty());
+
+    assert!(ts.split_at_checked(1).is_none());
+    assert!(ts.split_at_checked(5).is_none());
+}
+
+#[test]
+fn char_indices() {
+    let ts = Str::new(Sty::bold(), "日") + Str::new(Sty::dim(), "") + Str::from("a");
+    let indices: Vec<_> = ts.char_indices().collect();
+    assert_eq!(indices, [(0, '日'), (3, 'a')]);
+}
+
+// Splitting
+
+#[test]
+fn lines() {
+    let ts = Str::new(Sty::bold(), "one\ntw") + Str::new(Sty::dim(), "o\r\n\nthree");
+    let lines: Vec<_> = ts.lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert_eq!(lines[0].as_string(), "one");
+    assert_eq!(lines[0].elements[0].style, Sty::bold());
+    assert_eq!(lines[1].as_string(), "two");
+    assert_eq!(lines[1].elements.len(), 2);
+    assert_eq!(lines[1].elements[1].style, Sty::dim());
+    assert!(lines[2].is_empty());
+    assert_eq!(lines[3].as_string(), "three");
+
+    assert_eq!(Str::from("").lines().count(), 0);
+}
+
+#[test]
+fn split() {
+    let ts = Str::new(Sty::bold(), "a::b:") + Str::new(Sty::dim(), ":c::");
+    let pieces: Vec<_> = ts.split("::").collect();
+    let strs: Vec<_> = pieces.iter().map(|p| p.as_string()).collect();
+    assert_eq!(strs, ["a", "b", "c", ""]);
+    assert_eq!(pieces[2].elements[0].style, Sty::dim());
+}
+
+#[test]
+fn splitn() {
+    let ts = Str::new(Sty::bold(), "a b ") + Str::new(Sty::dim(), "c d");
+    let pieces: Vec<_> = ts.splitn(3, " ").collect();
+    assert_eq!(pieces.len(), 3);
+    assert_eq!(pieces[2].as_string(), "c d");
+    assert_eq!(pieces[2].elements[0].style, Sty::dim());
+}
+
+#[test]
+fn split_whitespace() {
+    let ts = Str::new(Sty::bold(), "  a b") + Str::new(Sty::dim(), "c \n d ");
+    let pieces: Vec<_> = ts.split_whitespace().collect();
+    let strs: Vec<_> = pieces.iter().map(|p| p.as_string()).collect();
+    assert_eq!(strs, ["a", "bc", "d"]);
+    assert_eq!(pieces[1].elements.len(), 2);
+}
+
+#[test]
+fn split_like_str() {
+    // Split between two styles at every char boundary, as str does
+    let texts = ["", "\n", "\r\n", "a\r", "a\n\r", "\r\r\n\n", "ab::c", "::", "日本 語\t", " \u{3000}x "];
+
+    for text in texts.iter() {
+        for mid in (0..=text.len()).filter(|&mid| text.is_char_boundary(mid)) {
+            let ts = Str::new(Sty::bold(), &text[..mid]) + Str::new(Sty::dim(), &text[mid..]);
+            let strs = |pieces: Vec<Str>| pieces.iter().map(|p| p.as_string()).collect::<Vec<_>>();
+
+            assert_eq!(strs(ts.lines().collect()), text.lines().collect::<Vec<_>>());
+            assert_eq!(strs(ts.split_whitespace().collect()), text.split_whitespace().collect::<Vec<_>>());
+            for pat in &["", ":", "::", "\n", "日"] {
+                assert_eq!(strs(ts.split(pat).collect()), text.split(pat).collect::<Vec<_>>());
+                for n in 0..4 {
+                    assert_eq!(strs(ts.splitn(n, pat).collect()), text.splitn(n, pat).collect::<Vec<_>>());
+                }
+            }
+        }
+    }
+}
+
+// Range styling
+
+#[test]
+fn style_range() {
+    let fg = Sty::fg(RED);
+    let mut ts = Str::new(fg, "abc") + Str::new(Sty::dim(), "def");
+    ts.style_range(1..5, Sty::fg(BLUE) + Sty::bold(), MergeMode::Add);
+    assert_eq!(ts.as_string(), "abcdef");
+    assert_eq!(ts.elements.len(), 4);
+    assert_eq!(ts.elements[0].text, "a");
+    assert_eq!(ts.elements[0].style, fg);
+    assert_eq!(ts.elements[1].text, "bc");
+    assert_eq!(ts.elements[1].style, Sty::fg(BLUE) + Sty::bold());
+    assert_eq!(ts.elements[2].text, "de");
+    assert_eq!(ts.elements[2].style, Sty::dim() + Sty::fg(BLUE) + Sty::bold());
+    assert_eq!(ts.elements[3].text, "f");
+    assert_eq!(ts.elements[3].style, Sty::dim());
+
+    // fill-in keeps existing colors
+    let mut ts = Str::new(fg, "abc") + Str::from("def");
+    ts.style_range(2.., Sty::fg(BLUE), MergeMode::Or);
+    assert_eq!(ts.elements.len(), 2);
+    assert_eq!(ts.elements[0].text, "abc");
+    assert_eq!(ts.elements[1].style, Sty::fg(BLUE));
+
+    // an empty range changes nothing
+    let mut ts = Str::new(fg, "abc");
+    ts.style_range(1..1, Sty::bold(), MergeMode::Add);
+    assert_eq!(ts.elements.len(), 1);
+
+    // the style of a trailing empty element is still inherited
+    let mut ts = Str::from("abc") + Str::new(Sty::bold(), "");
+    ts.style_range(..1, Sty::dim(), MergeMode::Add);
+    ts += "d";
+    assert_eq!(ts.elements.last().unwrap().text, "d");
+    assert_eq!(ts.elements.last().unwrap().style, Sty::bold());
+}
+
+#[test]
+#[should_panic]
+fn style_range_not_char_boundary() {
+    Str::from("é").style_range(..1, Sty::bold(), MergeMode::Add);
+}
+
+#[test]
+fn style_char_range() {
+    let ts = Str::from("日本語").with_styled_char_range(1..2, Sty::bold(), MergeMode::Add);
+    assert_eq!(ts.elements.len(), 3);
+    assert_eq!(ts.elements[1].text, "本");
+    assert_eq!(ts.elements[1].style, Sty::bold());
+}
+
+// Highlighting
+
+#[test]
+fn highlight() {
+    let fg = Sty::fg(RED);
+    let mut ts = Str::new(fg, "abab") + Str::from("ab");
+    ts.highlight("ba", Sty::bold());
+    assert_eq!(
+        elements(&ts),
+        [
+            ("a", fg),
+            ("bab", fg + Sty::bold()),
+            ("a", Sty::bold()),
+            ("b", Sty::default()),
+        ]
+    );
+
+    // no matches, or an empty pattern
+    let ts = Str::new(fg, "abc").with_highlighted("x", Sty::bold());
+    assert_eq!(ts.elements.len(), 1);
+    let ts = Str::new(fg, "abc").with_highlighted("", Sty::bold());
+    assert_eq!(ts.elements.len(), 1);
+}
+
+#[test]
+fn highlight_many() {
+    // Matches spanning, starting and ending at element boundaries,
+    // done in one pass, give the same result as styling each match
+    let mut ts = Str::default();
+    for i in 0..200 {
+        ts += Str::new(Sty::fg(i % 3), "ab") + Str::new(Sty::dim(), "") + Str::from("ba");
+    }
+
+    let mut expected = ts.clone();
+    for (start, _) in ts.as_string().match_indices("bb") {
+        expected.style_range(start..start + 2, Sty::bold(), MergeMode::Add);
+    }
+
+    ts.highlight("bb", Sty::bold());
+    assert_eq!(elements(&ts), elements(&expected));
+    assert_eq!(ts.elements.len(), 200 * 4);
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn highlight_regex() {
+    use regex::Regex;
+
+    let re = Regex::new("b+").unwrap();
+    let ts = (Str::new(Sty::dim(), "abb") + Str::from("bcb")).with_highlighted_regex(&re, Sty::bold());
+    assert_eq!(
+        elements(&ts),
+        [
+            ("a", Sty::dim()),
+            ("bb", Sty::dim() + Sty::bold()),
+            ("b", Sty::bold()),
+            ("c", Sty::default()),
+            ("b", Sty::bold()),
+        ]
+    );
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn highlight_regex_captures() {
+    use regex::Regex;
+
+    // the optional group doesn't participate in the second match,
+    // and the third group has no style
+    let re = Regex::new("(a)(x)?(c)").unwrap();
+    let mut ts = Str::from("ac axc");
+    ts.highlight_regex_captures(&re, &[Sty::dim(), Sty::bold(), Sty::fg(RED)]);
+    assert_eq!(
+        elements(&ts),
+        [
+            ("a", Sty::dim() + Sty::bold()),
+            ("c", Sty::dim()),
+            (" ", Sty::default()),
+            ("a", Sty::dim() + Sty::bold()),
+            ("x", Sty::dim() + Sty::fg(RED)),
+            ("c", Sty::dim()),
+        ]
+    );
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn highlight_regex_captures_nested() {
+    use regex::Regex;
+
+    let re = Regex::new("(a(b)c)d").unwrap();
+    let ts = (Str::new(Sty::fg(RED), "xab") + Str::from("cdx"))
+        .with_highlighted_regex_captures(&re, &[Sty::dim(), Sty::bold(), Sty::underline(true)]);
+    assert_eq!(
+        elements(&ts),
+        [
+            ("x", Sty::fg(RED)),
+            ("a", Sty::fg(RED) + Sty::dim() + Sty::bold()),
+            ("b", Sty::fg(RED) + Sty::dim() + Sty::bold() + Sty::underline(true)),
+            ("c", Sty::dim() + Sty::bold()),
+            ("d", Sty::dim()),
+            ("x", Sty::default()),
+        ]
+    );
+}
+
+// Replacing
+
+#[test]
+fn replace() {
+    let ts = Str::new(Sty::bold(), "a{x}") + Str::new(Sty::dim(), "b{x}");
+
+    // plain strings inherit the style at the match position
+    let replaced = ts.replace("{x}", "1");
+    assert_eq!(elements(&replaced), [("a1", Sty::bold()), ("b1", Sty::dim())]);
+
+    // styled replacements keep their styles
+    let one = Str::new(Sty::fg(RED), "1");
+    let replaced = ts.replace("{x}", &one);
+    assert_eq!(
+        elements(&replaced),
+        [("a", Sty::bold()), ("1", Sty::fg(RED)), ("b", Sty::dim()), ("1", Sty::fg(RED))]
+    );
+
+    // a match spanning styles inherits the style of its start
+    let replaced = ts.replace("}b", String::from("-"));
+    assert_eq!(replaced.as_string(), "a{x-{x}");
+    assert_eq!(replaced.elements[0].text, "a{x-");
+
+    // removing matches
+    assert_eq!(ts.replace("{x}", "").as_string(), "ab");
+    assert_eq!(ts.replace("y", "z").as_string(), "a{x}b{x}");
+}
+
+#[test]
+fn replace_many() {
+    let mut ts = Str::default();
+    for i in 0..100 {
+        ts += Str::new(Sty::fg(i % 3), "a-") + Str::new(Sty::dim(), "") + Str::from("-b");
+    }
+    let text = ts.as_string();
+
+    for pat in &["-", "--", "a--b", "b", ""] {
+        assert_eq!(ts.replace(pat, "+").as_string(), text.replace(pat, "+"));
+
+        // replacing matches with themselves changes nothing
+        assert_eq!(ts.replace_with(pat, |m| m.clone()), ts);
+        assert_eq!(ts.replace_with(pat, |m| Str::from(m.as_string())).as_string(), text);
+    }
+
+    // plain replacements take the style at the start of each match
+    let replaced = ts.replace("--", "=");
+    assert_eq!(replaced.style_at(1), Some(Sty::fg(0)));
+    assert_eq!(replaced.as_string()[..6], *"a=ba=b");
+    assert_eq!(replaced.style_at(4), Some(Sty::fg(1)));
+}
+
+#[test]
+fn replacen() {
+    let ts = Str::new(Sty::bold(), "a.b.") + Str::new(Sty::dim(), "c.");
+    assert_eq!(ts.replacen(".", "", 2).as_string(), "abc.");
+    assert_eq!(ts.replacen(".", "", 0).as_string(), "a.b.c.");
+    let replaced = ts.replacen(".", Str::from("!"), 5);
+    assert_eq!(replaced.as_string(), "a!b!c!");
+    assert_eq!(replaced.elements.len(), 6);
+}
+
+#[test]
+fn replace_with() {
+    let ts = Str::new(Sty::bold(), "ab") + Str::new(Sty::dim(), "cab");
+    let mut count = 0;
+    let replaced = ts.replace_with("ab", |m| {
+        count += 1;
+        assert_eq!(m.as_string(), "ab");
+        m.clone().with_style(Sty::underline(true))
+    });
+    assert_eq!(count, 2);
+    assert_eq!(
+        elements(&replaced),
+        [
+            ("ab", Sty::bold() + Sty::underline(true)),
+            ("c", Sty::dim()),
+            ("ab", Sty::dim() + Sty::underline(true)),
+        ]
+    );
+}
+
+// Editing
+
+#[test]
+fn insert() {
+    let mut ts = Str::new(Sty::bold(), "ad") + Str::new(Sty::dim(), "e");
+    ts.insert(1, Str::new(Sty::dim(), "b") + Str::new(Sty::bold(), "c"));
+    assert_eq!(elements(&ts), [("a", Sty::bold()), ("b", Sty::dim()), ("cd", Sty::bold()), ("e", Sty::dim())]);
+
+    // at element boundaries, and at the ends
+    let mut ts = Str::new(Sty::bold(), "b") + Str::new(Sty::dim(), "d");
+    ts.insert(1, Str::new(Sty::dim(), "c"));
+    ts.insert(0, Str::new(Sty::bold(), "a"));
+    ts.insert(4, "e");
+    assert_eq!(elements(&ts), [("ab", Sty::bold()), ("cd", Sty::dim()), ("e", Sty::default())]);
+
+    let ts = Str::default().with_inserted(0, "a");
+    assert_eq!(ts.as_string(), "a");
+}
+
+#[test]
+#[should_panic]
+fn insert_out_of_bounds() {
+    Str::from("a").insert(2, "b");
+}
+
+#[test]
+fn insert_str() {
+    let mut ts = Str::new(Sty::bold(), "ac") + Str::new(Sty::dim(), "d");
+    ts.insert_str(1, "b");
+    ts.insert_str(3, "-");
+    ts.insert_str(5, "e");
+    ts.insert_str(0, "<");
+    // at a boundary, the preceding style is inherited
+    assert_eq!(elements(&ts), [("<abc-", Sty::bold()), ("de", Sty::dim())]);
+}
+
+#[test]
+fn prepend() {
+    let ts = Str::new(Sty::bold(), "b").with_prepended(Str::new(Sty::bold(), "a"));
+    assert_eq!(elements(&ts), [("ab", Sty::bold())]);
+}
+
+#[test]
+fn remove() {
+    let mut ts = Str::new(Sty::bold(), "ab") + Str::new(Sty::dim(), "cd") + Str::new(Sty::bold(), "ef");
+    let removed = ts.remove(1..5);
+    assert_eq!(elements(&ts), [("af", Sty::bold())]);
+    assert_eq!(elements(&removed), [("b", Sty::bold()), ("cd", Sty::dim()), ("e", Sty::bold())]);
+
+    let removed = ts.remove(..);
+    assert!(ts.is_empty());
+    assert_eq!(removed.as_string(), "af");
+
+    // the style of an empty last element is kept
+    let mut ts = Str::from("ab") + Str::new(Sty::bold(), "");
+    ts.remove(1..);
+    ts += "c";
+    assert_eq!(elements(&ts), [("a", Sty::default()), ("c", Sty::bold())]);
+}
+
+#[test]
+fn truncate() {
+    let mut ts = Str::new(Sty::bold(), "ab") + Str::new(Sty::dim(), "cd");
+    ts.truncate(10);
+    assert_eq!(ts.as_string(), "abcd");
+    ts.truncate(3);
+    assert_eq!(elements(&ts), [("ab", Sty::bold()), ("c", Sty::dim())]);
+    assert!(ts.with_truncated(0).is_empty());
+}
+
+#[test]
+fn clear() {
+    let mut ts = Str::new(Sty::bold(), "ab") + Str::new(Sty::dim(), "cd");
+    ts.clear();
+    assert!(ts.is_empty());
+    ts += "a";
+    assert_eq!(elements(&ts), [("a", Sty::default())]);
+}
+
+#[test]
+fn pop() {
+    let mut ts = Str::new(Sty::bold(), "a") + Str::new(Sty::dim(), "b") + Str::new(Sty::bold(), "");
+    assert_eq!(ts.pop(), Some('b'));
+    assert_eq!(elements(&ts), [("a", Sty::bold())]);
+    assert_eq!(ts.pop(), Some('a'));
+    assert_eq!(ts.pop(), None);
+}
+
+// Spans
+
+#[test]
+fn spans() {
+    let ts = Str::new(Sty::bold(), "a") + Str::new(Sty::dim(), "b");
+    let spans: Vec<_> = ts.spans().collect();
+    assert_eq!(spans, [(&Sty::bold(), "a"), (&Sty::dim(), "b")]);
+    assert_eq!(Str::default().spans().count(), 0);
+}
+
+#[test]
+fn spans_mut() {
+    let mut ts = Str::new(Sty::bold(), "a") + Str::new(Sty::dim(), "b");
+    for (style, text) in ts.spans_mut() {
+        style.add_fg(RED);
+        text.push('!');
+    }
+    assert_eq!(
+        elements(&ts),
+        [("a!", Sty::bold() + Sty::fg(RED)), ("b!", Sty::dim() + Sty::fg(RED))]
+    );
+}
+
+#[test]
+fn styled_chars() {
+    let ts = Str::new(Sty::bold(), "日") + Str::new(Sty::dim(), "") + Str::from("a");
+    let chars: Vec<_> = ts.styled_chars().collect();
+    assert_eq!(chars, [('日', Sty::bold()), ('a', Sty::default())]);
+}
+
+#[test]
+fn style_at() {
+    let ts = Str::new(Sty::bold(), "日") + Str::new(Sty::dim(), "") + Str::from("a");
+    assert_eq!(ts.style_at(0), Some(Sty::bold()));
+    assert_eq!(ts.style_at(2), None);
+    assert_eq!(ts.style_at(3), Some(Sty::default()));
+    assert_eq!(ts.style_at(4), None);
+}
+
+#[test]
+fn from_iter_spans() {
+    let spans = vec![
+        (Sty::bold(), String::from("a")),
+        (Sty::bold(), String::from("b")),
+        (Sty::dim(), String::new()),
+        (Sty::dim(), String::from("c")),
+    ];
+    let ts: Str = spans.into_iter().collect();
+    assert_eq!(elements(&ts), [("ab", Sty::bold()), ("c", Sty::dim())]);
+}
+
+// Collecting
+
+#[test]
+fn from_iter() {
+    let parts = vec![Str::new(Sty::bold(), "a"), Str::new(Sty::bold(), "b"), Str::from(""), Str::from("c")];
+    let ts: Str = parts.into_iter().collect();
+    assert_eq!(elements(&ts), [("ab", Sty::bold()), ("c", Sty::default())]);
+
+    let ts: Str = vec!["a", "b"].into_iter().collect();
+    assert_eq!(elements(&ts), [("ab", Sty::default())]);
+
+    let ts: Str = vec![String::from("a")].into_iter().collect();
+    assert_eq!(ts.as_string(), "a");
+
+    let ts: Str = Vec::<Str>::new().into_iter().collect();
+    assert!(ts.is_empty());
+}
+
+#[test]
+fn extend() {
+    let mut ts = Str::new(Sty::bold(), "a");
+    ts.extend(vec![Str::new(Sty::bold(), "b"), Str::new(Sty::dim(), "c")]);
+    ts.extend(vec!["d"]);
+    ts.extend(vec![String::from("e")]);
+    assert_eq!(elements(&ts), [("ab", Sty::bold()), ("cde", Sty::dim())]);
+}
+
+#[test]
+fn sum() {
+    let ts: Str = vec![Str::new(Sty::bold(), "a"), Str::new(Sty::dim(), "b")].into_iter().sum();
+    assert_eq!(elements(&ts), [("a", Sty::bold()), ("b", Sty::dim())]);
+}
+
+#[test]
+fn collect_many() {
+    // Empty parts in between are dropped, and equal styles are merged
+    let parts = (0..20_000).map(|i| match i % 3 {
+        0 => Str::new(Sty::fg(i % 2), "a"),
+        1 => Str::default(),
+        _ => Str::new(Sty::fg(i % 2), "b"),
+    });
+    let ts: Str = parts.collect();
+    assert_eq!(ts.len(), 20_000 / 3 * 2 + 1);
+    assert!(ts.elements.iter().all(|e| !e.text.is_empty()));
+    assert!(ts.elements.windows(2).all(|w| w[0].style != w[1].style));
+}
+
+#[test]
+fn join() {
+    let sep = Str::new(Sty::dim(), ", ");
+    let ts = Str::join(vec![Str::new(Sty::bold(), "a"), Str::new(Sty::dim(), "b")], sep.clone());
+    assert_eq!(elements(&ts), [("a", Sty::bold()), (", b", Sty::dim())]);
+
+    assert_eq!(Str::join(vec!["a"], sep.clone()).as_string(), "a");
+    assert!(Str::join(Vec::<&str>::new(), sep).is_empty());
+}
+
+// Sanitizing
+
+#[test]
+fn sanitizer() {
+    let text = "a\x00\x1b]0;title\x07\x7f\u{9b}2J\r\n";
+
+    let replace = Sanitizer::new(SanitizeMode::Replace);
+    assert_eq!(replace.sanitize(text), "a␀␛]0;title␇␡\u{fffd}2J␍␊");
+
+    let escape = Sanitizer::new(SanitizeMode::Escape).with_allowed('\n');
+    assert_eq!(escape.sanitize(text), "a\\u{0}\\u{1b}]0;title\\u{7}\\u{7f}\\u{9b}2J\\r\n");
+
+    let remove = Sanitizer::new(SanitizeMode::Remove);
+    assert_eq!(remove.sanitize(text), "a2J");
+
+    // ESC allowed, so sequences are kept, but other controls are removed
+    let remove = Sanitizer::new(SanitizeMode::Remove).with_allowed('\x1b');
+    assert_eq!(remove.sanitize("\x1b[1mbold\x07"), "\x1b[1mbold");
+
+    // non-control chars are never touched
+    assert_eq!(Sanitizer::default().sanitize("日本 ✓\t\n"), "日本 ✓\t\n");
+}
+
+#[test]
+fn new_untrusted() {
+    let ts = Str::new_untrusted(Sty::bold(), "x\x1b[2Jy");
+    assert_eq!(elements(&ts), [("x␛[2Jy", Sty::bold())]);
+
+    let ts = Str::new_sanitized(Sty::bold(), "x\x1b[2Jy", &Sanitizer::new(SanitizeMode::Remove));
+    assert_eq!(ts.as_string(), "xy");
+}
+
+#[test]
+fn append_untrusted() {
+    let ts = Str::new(Sty::bold(), "a").with_appended_untrusted("\x1bb\n");
+    assert_eq!(elements(&ts), [("a␛b\n", Sty::bold())]);
+
+    let remove = Sanitizer::new(SanitizeMode::Remove);
+    let ts = Str::new(Sty::bold(), "a").with_appended_sanitized("\x1b[0mb", &remove);
+    assert_eq!(elements(&ts), [("ab", Sty::bold())]);
+}
+
+#[test]
+fn sanitize() {
+    let remove = Sanitizer::new(SanitizeMode::Remove);
+    let ts = (Str::new(Sty::bold(), "a\x07") + Str::new(Sty::dim(), "\x1b[H") + Str::new(Sty::bold(), "b"))
+        .with_sanitized(&remove);
+    assert_eq!(elements(&ts), [("ab", Sty::bold())]);
+}
+
+// Comparing
+
+fn hash_of<T: Hash>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[test]
+fn eq_hash() {
+    let ts1 = Str::new(Sty::bold(), "ab") + Str::new(Sty::fg(RED), "cd");
+    let mut ts2 =
+        Str::new(Sty::bold(), "a") + Str::new(Sty::bold(), "b") + Str::new(Sty::dim(), "");
+    ts2.elements.push(TermStringElement::new(Sty::fg(RED), "c"));
+    ts2.elements.push(TermStringElement::new(Sty::fg(RED), "d"));
+    assert!(ts1.elements.len() < ts2.elements.len());
+    assert_eq!(ts1, ts2);
+    assert_eq!(hash_of(&ts1), hash_of(&ts2));
+
+    // Same text, different styles
+    let ts3 = Str::new(Sty::bold(), "abc") + Str::new(Sty::fg(RED), "d");
+    assert_ne!(ts1, ts3);
+    assert_ne!(ts1, Str::from("abcd"));
+    assert_ne!(ts1, ts1.clone() + "e");
+
+    // The style of an empty last element is inherited by appended text
+    let bold = Str::new(Sty::bold(), "");
+    assert_ne!(bold, Str::default());
+    assert_ne!(bold.clone().with_appended_str("x"), Str::default().with_appended_str("x"));
+    assert_ne!(ts1.clone() + bold.clone(), ts1);
+    assert_eq!(Str::from(""), Str::default());
+    assert_eq!(hash_of(&Str::from("")), hash_of(&Str::default()));
+
+    // Empty elements elsewhere don't matter
+    let ts4 = Str::new(Sty::bold(), "ab") + Str::new(Sty::dim(), "") + Str::new(Sty::fg(RED), "cd");
+    assert_eq!(ts4, ts1);
+    assert_eq!(hash_of(&ts4), hash_of(&ts1));
+}
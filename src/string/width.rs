@@ -0,0 +1,422 @@
+/*
+    This file is a part of term-string.
+
+    Copyright (C) 2018 Mohammad AlSaleh <CE.Mohammad.AlSaleh at gmail.com>
+    https://github.com/rust-alt/term-string
+
+    This Source Code Form is subject to the terms of the Mozilla Public
+    License, v. 2.0. If a copy of the MPL was not distributed with this
+    file, You can obtain one at <http://mozilla.org/MPL/2.0/>.
+*/
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthChar;
+
+use style::TermStyle;
+
+use super::TermString;
+
+const VS16: char = '\u{fe0f}';
+
+fn is_regional_indicator(c: char) -> bool {
+    ('\u{1f1e6}'..='\u{1f1ff}').contains(&c)
+}
+
+// Columns taken by a grapheme cluster.
+//
+// A cluster is as wide as its widest char, so combining marks and
+// zero-width joined parts (e.g. in family emoji) take no extra columns.
+// Emoji presentation (VS16) and flags (regional indicator pairs) take 2.
+pub(crate) fn grapheme_width(g: &str) -> usize {
+    let mut chars = g.chars();
+    let is_flag = chars.next().map_or(false, is_regional_indicator)
+        && chars.next().map_or(false, is_regional_indicator);
+
+    if is_flag || g.contains(VS16) {
+        return 2;
+    }
+
+    g.chars().map(|c| c.width().unwrap_or(0)).max().unwrap_or(0)
+}
+
+// Columns taken by text.
+pub(crate) fn str_width(text: &str) -> usize {
+    text.graphemes(true).map(grapheme_width).sum()
+}
+
+// The end of the longest prefix of text fitting in width columns,
+// and the columns it takes.
+pub(crate) fn fit_start(text: &str, width: usize) -> (usize, usize) {
+    let mut used = 0;
+
+    for (i, g) in text.grapheme_indices(true) {
+        let w = grapheme_width(g);
+        if used + w > width {
+            return (i, used);
+        }
+        used += w;
+    }
+
+    (text.len(), used)
+}
+
+// The start of the longest suffix of text fitting in width columns.
+fn fit_end(text: &str, width: usize) -> usize {
+    let mut used = 0;
+
+    for (i, g) in text.grapheme_indices(true).rev() {
+        let w = grapheme_width(g);
+        if used + w > width {
+            return i + g.len();
+        }
+        used += w;
+    }
+
+    0
+}
+
+// Where truncated text is cut from.
+#[derive(Copy, Clone)]
+enum Cut {
+    Start,
+    Middle,
+    End,
+}
+
+/// Methods for measuring [`TermString`] as displayed in a terminal.
+impl TermString {
+    /// Return the number of terminal columns the un-styled string contained
+    /// in [`TermString`] takes.
+    ///
+    /// Unlike [`len()`], which returns the number of bytes, this follows
+    /// Unicode East Asian Width, and works on grapheme clusters. So wide
+    /// (e.g. CJK) characters take 2 columns, combining marks and zero-width
+    /// characters take none, and emoji sequences take 2 columns as a whole.
+    ///
+    /// Control characters are counted as zero-width.
+    ///
+    /// [`len()`]: TermString::len
+    ///
+    /// # Examples
+    ///
+    /// ``` rust
+    /// # use term_string::{TermString, TermStyle};
+    /// let ts = TermString::new(TermStyle::bold(), "café ") + "日本";
+    /// assert_eq!(ts.len(), 12);
+    /// assert_eq!(ts.width(), 9);
+    ///
+    /// // a family emoji, made of 3 emoji joined with zero-width joiners
+    /// let ts = TermString::from("\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}");
+    /// assert_eq!(ts.width(), 2);
+    /// ```
+    pub fn width(&self) -> usize {
+        // Clusters may span elements with different styles,
+        // so the text is measured as a whole.
+        str_width(&self.as_string())
+    }
+
+    /// Return the number of chars (Unicode scalar values) in the un-styled
+    /// string contained in [`TermString`].
+    ///
+    /// # Examples
+    ///
+    /// ``` rust
+    /// # use term_string::{TermString, TermStyle};
+    /// let ts = TermString::new(TermStyle::bold(), "café ") + "日本";
+    /// assert_eq!(ts.char_count(), 7);
+    /// ```
+    pub fn char_count(&self) -> usize {
+        self.elements.iter().map(|e| e.text.chars().count()).sum()
+    }
+}
+
+/// Methods for truncating [`TermString`] to fit in a number of terminal columns.
+///
+/// Text is always cut at grapheme cluster boundaries, and surviving text
+/// keeps its style. If [`TermString`] already fits, it's left as-is.
+/// Otherwise, `ellipsis` is inserted where text was cut, and the result
+/// takes at most `width` columns, ellipsis included.
+///
+/// `ellipsis` is appended as a [`TermString`]. So, a plain string ellipsis
+/// is un-styled. Pass a [`TermString`] to style it.
+impl TermString {
+    fn _truncate_to_width(&mut self, width: usize, ellipsis: Self, cut: Cut) {
+        let text = self.as_string();
+
+        if str_width(&text) <= width {
+            return;
+        }
+
+        let ellipsis_width = ellipsis.width();
+
+        // Not even the ellipsis fits, so it's truncated too
+        if ellipsis_width > width {
+            let end = fit_start(&ellipsis.as_string(), width).0;
+            *self = ellipsis._slice(0, end);
+            return;
+        }
+
+        let budget = width - ellipsis_width;
+        let (head_end, tail_start) = match cut {
+            Cut::End => (fit_start(&text, budget).0, text.len()),
+            Cut::Start => (0, fit_end(&text, budget)),
+            Cut::Middle => {
+                // The head gets the extra column, if any
+                let (head_end, used) = fit_start(&text, budget - budget / 2);
+                (head_end, fit_end(&text, budget - used).max(head_end))
+            },
+        };
+
+        let mut ts = self._slice(0, head_end);
+        ts.append_term_str(ellipsis);
+        ts.append_term_str(self._slice(tail_start, text.len()));
+        *self = ts;
+    }
+
+    /// Truncate [`TermString`] to `width` columns, cutting from the end.
+    ///
+    /// # Examples
+    ///
+    /// ``` rust
+    /// # use term_string::{TermString, TermStyle};
+    /// let mut ts = TermString::new(TermStyle::bold(), "fix") + TermString::from(": the bug");
+    /// ts.truncate_to_width(8, "…");
+    /// assert_eq!(ts.as_string(), "fix: th…");
+    ///
+    /// // wide chars are never split
+    /// let mut ts = TermString::from("日本語");
+    /// ts.truncate_to_width(4, "…");
+    /// assert_eq!(ts.as_string(), "日…");
+    /// ```
+    pub fn truncate_to_width<IS>(&mut self, width: usize, ellipsis: IS)
+    where
+        IS: Into<Self>,
+    {
+        self._truncate_to_width(width, ellipsis.into(), Cut::End);
+    }
+
+    /// Truncate [`TermString`] to `width` columns, cutting from the start.
+    ///
+    /// This is useful for paths, as they are most informative at the end.
+    ///
+    /// # Examples
+    ///
+    /// ``` rust
+    /// # use term_string::{color, TermString, TermStyle};
+    /// let ellipsis = TermString::new(TermStyle::fg(color::BLUE), "…");
+    /// let mut ts = TermString::from("rust-alt/term-string/src/string/mod.rs");
+    /// ts.truncate_start_to_width(19, ellipsis);
+    /// assert_eq!(ts.as_string(), "…/src/string/mod.rs");
+    /// ```
+    pub fn truncate_start_to_width<IS>(&mut self, width: usize, ellipsis: IS)
+    where
+        IS: Into<Self>,
+    {
+        self._truncate_to_width(width, ellipsis.into(), Cut::Start);
+    }
+
+    /// Truncate [`TermString`] to `width` columns, cutting from the middle.
+    ///
+    /// If the columns left for text can't be split evenly, the start gets
+    /// the extra column.
+    ///
+    /// # Examples
+    ///
+    /// ``` rust
+    /// # use term_string::TermString;
+    /// let mut ts = TermString::from("term-string/src/string/mod.rs");
+    /// ts.truncate_middle_to_width(15, "…");
+    /// assert_eq!(ts.as_string(), "term-st…/mod.rs");
+    /// ```
+    pub fn truncate_middle_to_width<IS>(&mut self, width: usize, ellipsis: IS)
+    where
+        IS: Into<Self>,
+    {
+        self._truncate_to_width(width, ellipsis.into(), Cut::Middle);
+    }
+
+    chaining_fn!(TermString, truncate_to_width,
+                 pub fn with_truncated_to_width<IS>(mut self, width: usize, ellipsis: IS) -> Self
+                 where
+                     IS: Into<Self>,
+                 {
+                     self.truncate_to_width(width, ellipsis);
+                     self
+                 }
+    );
+
+    chaining_fn!(TermString, truncate_start_to_width,
+                 pub fn with_start_truncated_to_width<IS>(mut self, width: usize, ellipsis: IS) -> Self
+                 where
+                     IS: Into<Self>,
+                 {
+                     self.truncate_start_to_width(width, ellipsis);
+                     self
+                 }
+    );
+
+    chaining_fn!(TermString, truncate_middle_to_width,
+                 pub fn with_middle_truncated_to_width<IS>(mut self, width: usize, ellipsis: IS) -> Self
+                 where
+                     IS: Into<Self>,
+                 {
+                     self.truncate_middle_to_width(width, ellipsis);
+                     self
+                 }
+    );
+}
+
+/// Methods for padding [`TermString`] to a number of terminal columns,
+/// for aligning text in columns.
+///
+/// Padding is made of `fill` repeated, and the last repetition is truncated
+/// if it doesn't fit whole. A column left by a wide character that doesn't
+/// fit is filled with a space, so padded text is always exactly `width`
+/// columns. If [`TermString`] is already as wide as `width` or wider,
+/// nothing is added.
+///
+/// `fill` keeps its style, and inherits the background color of the
+/// adjacent text, unless it has its own. So backgrounds extend across
+/// padded cells.
+impl TermString {
+    // Padding of cols columns, inheriting the background of adjacent.
+    fn _padding(cols: usize, fill: &Self, adjacent: Option<TermStyle>) -> Self {
+        let mut padding = Self::default();
+        let fill_str = fill.as_string();
+        let fill_width = str_width(&fill_str);
+
+        if fill_width == 0 {
+            return padding;
+        }
+
+        for _ in 0..cols / fill_width {
+            padding.append_term_str(fill.clone());
+        }
+        let (end, used) = fit_start(&fill_str, cols % fill_width);
+        padding.append_term_str(fill._slice(0, end));
+
+        // A wide grapheme cut at the end leaves columns only spaces can fill
+        let left = cols % fill_width - used;
+        if left > 0 {
+            let bg = fill._style_at(end)._bg_style();
+            padding.append_term_str(Self::new(bg, " ".repeat(left)));
+        }
+
+        if let Some(adjacent) = adjacent {
+            let bg = adjacent._bg_style();
+            for e in &mut padding.elements {
+                e.style |= bg;
+            }
+        }
+
+        padding
+    }
+
+    // The style of the first (or last) element with text, if any.
+    fn _edge_style(&self, last: bool) -> Option<TermStyle> {
+        let mut with_text = self.elements.iter().filter(|e| !e.text.is_empty());
+        let e = if last { with_text.next_back() } else { with_text.next() };
+        e.map(|e| e.style)
+    }
+
+    // Pad with left_cols columns on the left, and right_cols on the right.
+    fn _pad(&mut self, left_cols: usize, right_cols: usize, fill: Self) {
+        let mut ts = Self::_padding(left_cols, &fill, self._edge_style(false));
+        let right = Self::_padding(right_cols, &fill, self._edge_style(true));
+
+        ts.append_term_str(self.clone());
+        ts.append_term_str(right);
+        *self = ts;
+    }
+
+    /// Pad [`TermString`] on the left to `width` columns, aligning it to the right.
+    ///
+    /// # Examples
+    ///
+    /// ``` rust
+    /// # use term_string::{color, TermString, TermStyle};
+    /// let mut ts = TermString::new(TermStyle::bg(color::BLUE), "42");
+    /// ts.pad_left(5, " ");
+    /// assert_eq!(ts.as_string(), "   42");
+    /// // the padding inherited the blue background
+    /// assert_eq!(ts.to_ansi_string(), "\x1b[44m   42\x1b[0m");
+    /// ```
+    pub fn pad_left<IS>(&mut self, width: usize, fill: IS)
+    where
+        IS: Into<Self>,
+    {
+        let cols = width.saturating_sub(self.width());
+        self._pad(cols, 0, fill.into());
+    }
+
+    /// Pad [`TermString`] on the right to `width` columns, aligning it to the left.
+    ///
+    /// # Examples
+    ///
+    /// ``` rust
+    /// # use term_string::{TermString, TermStyle};
+    /// // dotted leaders
+    /// let mut ts = TermString::from("Chapter 1 ");
+    /// ts.pad_right(16, TermString::new(TermStyle::dim(), "."));
+    /// // Appending a &str would make " 7" dim too
+    /// ts += TermString::from(" 7");
+    /// assert_eq!(ts.as_string(), "Chapter 1 ...... 7");
+    /// ```
+    pub fn pad_right<IS>(&mut self, width: usize, fill: IS)
+    where
+        IS: Into<Self>,
+    {
+        let cols = width.saturating_sub(self.width());
+        self._pad(0, cols, fill.into());
+    }
+
+    /// Pad [`TermString`] on both sides to `width` columns, centering it.
+    ///
+    /// If padding can't be split evenly, the right side gets the extra column.
+    ///
+    /// # Examples
+    ///
+    /// ``` rust
+    /// # use term_string::{TermString, TermStyle};
+    /// let mut ts = TermString::new(TermStyle::bold(), "title");
+    /// ts.center(10, "-");
+    /// assert_eq!(ts.as_string(), "--title---");
+    /// ```
+    pub fn center<IS>(&mut self, width: usize, fill: IS)
+    where
+        IS: Into<Self>,
+    {
+        let cols = width.saturating_sub(self.width());
+        self._pad(cols / 2, cols - cols / 2, fill.into());
+    }
+
+    chaining_fn!(TermString, pad_left,
+                 pub fn with_padded_left<IS>(mut self, width: usize, fill: IS) -> Self
+                 where
+                     IS: Into<Self>,
+                 {
+                     self.pad_left(width, fill);
+                     self
+                 }
+    );
+
+    chaining_fn!(TermString, pad_right,
+                 pub fn with_padded_right<IS>(mut self, width: usize, fill: IS) -> Self
+                 where
+                     IS: Into<Self>,
+                 {
+                     self.pad_right(width, fill);
+                     self
+                 }
+    );
+
+    chaining_fn!(TermString, center,
+                 pub fn with_centered<IS>(mut self, width: usize, fill: IS) -> Self
+                 where
+                     IS: Into<Self>,
+                 {
+                     self.center(width, fill);
+                     self
+                 }
+    );
+}
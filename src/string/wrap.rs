@@ -0,0 +1,242 @@
+/*
+    This file is a part of term-string.
+
+    Copyright (C) 2018 Mohammad AlSaleh <CE.Mohammad.AlSaleh at gmail.com>
+    https://github.com/rust-alt/term-string
+
+    This Source Code Form is subject to the terms of the Mozilla Public
+    License, v. 2.0. If a copy of the MPL was not distributed with this
+    file, You can obtain one at <http://mozilla.org/MPL/2.0/>.
+*/
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::width::{fit_start, grapheme_width, str_width};
+use super::TermString;
+
+// Runs of whitespace and non-whitespace in text, with their byte offsets.
+fn tokens(text: &str) -> Vec<(usize, &str)> {
+    let mut tokens = Vec::new();
+    let mut start = 0;
+
+    for (i, c) in text.char_indices().skip(1) {
+        let prev_ws = text[..i].ends_with(char::is_whitespace);
+        if c.is_whitespace() != prev_ws {
+            tokens.push((start, &text[start..i]));
+            start = i;
+        }
+    }
+
+    if start < text.len() {
+        tokens.push((start, &text[start..]));
+    }
+
+    tokens
+}
+
+// Byte ranges of the lines text is wrapped into. The first line may take
+// first_width columns, and the rest may take width columns.
+//
+// Explicit newlines are kept. Whitespace where lines are broken is dropped,
+// except for whitespace at the start of a paragraph.
+fn wrap_ranges(text: &str, first_width: usize, width: usize) -> Vec<(usize, usize)> {
+    let mut lines = Vec::new();
+    let mut para_start = 0;
+
+    for para in text.split('\n') {
+        let mut line: Option<(usize, usize)> = None;
+        let mut line_width = 0;
+        let mut ws_width = 0;
+
+        for (offset, token) in tokens(para) {
+            let (start, end) = (para_start + offset, para_start + offset + token.len());
+            let token_width = str_width(token);
+            let avail = if lines.is_empty() { first_width } else { width };
+
+            if token.starts_with(char::is_whitespace) {
+                match line {
+                    Some(_) => ws_width += token_width,
+                    None if offset == 0 => {
+                        line = Some((start, end));
+                        line_width = token_width;
+                    },
+                    None => (),
+                }
+                continue;
+            }
+
+            match line {
+                Some((line_start, _)) if line_width + ws_width + token_width <= avail => {
+                    line = Some((line_start, end));
+                    line_width += ws_width + token_width;
+                    ws_width = 0;
+                    continue;
+                },
+                Some(range) => lines.push(range),
+                None => (),
+            }
+
+            ws_width = 0;
+
+            // Hard-break words longer than a line
+            let mut word_start = start;
+            loop {
+                let avail = if lines.is_empty() { first_width } else { width };
+                let word = &text[word_start..end];
+                let (mut len, mut used) = fit_start(word, avail);
+
+                // At least one grapheme goes in a line
+                if len == 0 {
+                    let g = word.graphemes(true).next().expect("should never happen");
+                    len = g.len();
+                    used = grapheme_width(g);
+                }
+
+                if word_start + len == end {
+                    line = Some((word_start, end));
+                    line_width = used;
+                    break;
+                }
+
+                lines.push((word_start, word_start + len));
+                word_start += len;
+            }
+        }
+
+        lines.push(line.unwrap_or((para_start, para_start)));
+        para_start += para.len() + 1;
+    }
+
+    lines
+}
+
+/// Methods for wrapping [`TermString`] into lines fitting in a number
+/// of terminal columns.
+///
+/// Lines are broken on whitespace, and widths are measured as displayed
+/// in a terminal (check out [`width()`]). Words longer than a line are
+/// broken at grapheme cluster boundaries. Explicit newlines are kept.
+///
+/// Wrapped text keeps its style. Indents are prepended as [`TermString`]s.
+/// So, plain string indents are un-styled.
+///
+/// [`width()`]: TermString::width
+impl TermString {
+    /// Wrap [`TermString`] into lines of at most `width` columns.
+    ///
+    /// # Examples
+    ///
+    /// ``` rust
+    /// # use term_string::{TermString, TermStyle};
+    /// let ts = TermString::new(TermStyle::bold(), "error:") + TermString::from(" file not found");
+    /// let lines = ts.wrap(12);
+    /// assert_eq!(lines.len(), 2);
+    /// assert_eq!(lines[0].as_string(), "error: file");
+    /// assert_eq!(lines[1].as_string(), "not found");
+    /// ```
+    pub fn wrap(&self, width: usize) -> Vec<Self> {
+        self.wrap_indented(width, "", "")
+    }
+
+    /// Wrap [`TermString`] into lines of at most `width` columns, indents
+    /// included.
+    ///
+    /// The first line is prefixed with `initial_indent`, and the rest of the
+    /// lines are prefixed with `subsequent_indent`.
+    ///
+    /// # Examples
+    ///
+    /// ``` rust
+    /// # use term_string::{color, TermString, TermStyle};
+    /// let bullet = TermString::new(TermStyle::fg(color::BLUE), "* ");
+    /// let ts = TermString::from("a long item in a list");
+    /// let lines = ts.wrap_indented(10, bullet, "  ");
+    /// assert_eq!(lines.len(), 3);
+    /// assert_eq!(lines[0].as_string(), "* a long");
+    /// assert_eq!(lines[1].as_string(), "  item in");
+    /// assert_eq!(lines[2].as_string(), "  a list");
+    /// ```
+    pub fn wrap_indented<IS1, IS2>(
+        &self,
+        width: usize,
+        initial_indent: IS1,
+        subsequent_indent: IS2,
+    ) -> Vec<Self>
+    where
+        IS1: Into<Self>,
+        IS2: Into<Self>,
+    {
+        let initial_indent = initial_indent.into();
+        let subsequent_indent = subsequent_indent.into();
+
+        let first_width = width.saturating_sub(initial_indent.width());
+        let rest_width = width.saturating_sub(subsequent_indent.width());
+
+        wrap_ranges(&self.as_string(), first_width, rest_width)
+            .into_iter()
+            .enumerate()
+            .map(|(i, (start, end))| {
+                let mut line = match i {
+                    0 => initial_indent.clone(),
+                    _ => subsequent_indent.clone(),
+                };
+                line.append_term_str(self._slice(start, end));
+                line
+            })
+            .collect()
+    }
+
+    /// Wrap [`TermString`] into lines of at most `width` columns, and join
+    /// them with newlines.
+    ///
+    /// # Examples
+    ///
+    /// ``` rust
+    /// # use term_string::{TermString, TermStyle};
+    /// let ts = TermString::new(TermStyle::bold(), "error:") + TermString::from(" file not found");
+    /// assert_eq!(ts.fill(12).as_string(), "error: file\nnot found");
+    /// ```
+    pub fn fill(&self, width: usize) -> Self {
+        self.fill_indented(width, "", "")
+    }
+
+    /// Wrap [`TermString`] into lines of at most `width` columns, indents
+    /// included, and join them with newlines.
+    ///
+    /// Check out [`wrap_indented()`] for details.
+    ///
+    /// [`wrap_indented()`]: TermString::wrap_indented
+    ///
+    /// # Examples
+    ///
+    /// ``` rust
+    /// # use term_string::TermString;
+    /// let ts = TermString::from("usage: prog [options] <file>");
+    /// assert_eq!(ts.fill_indented(16, "", "    ").as_string(), "usage: prog\n    [options]\n    <file>");
+    /// ```
+    pub fn fill_indented<IS1, IS2>(
+        &self,
+        width: usize,
+        initial_indent: IS1,
+        subsequent_indent: IS2,
+    ) -> Self
+    where
+        IS1: Into<Self>,
+        IS2: Into<Self>,
+    {
+        let mut ts = Self::default();
+
+        for (i, line) in self
+            .wrap_indented(width, initial_indent, subsequent_indent)
+            .into_iter()
+            .enumerate()
+        {
+            if i > 0 {
+                ts.append_term_str("\n");
+            }
+            ts.append_term_str(line);
+        }
+
+        ts
+    }
+}
@@ -0,0 +1,244 @@
+/*
+    This file is a part of term-string.
+
+    Copyright (C) 2018 Mohammad AlSaleh <CE.Mohammad.AlSaleh at gmail.com>
+    https://github.com/rust-alt/term-string
+
+    This Source Code Form is subject to the terms of the Mozilla Public
+    License, v. 2.0. If a copy of the MPL was not distributed with this
+    file, You can obtain one at <http://mozilla.org/MPL/2.0/>.
+*/
+
+use term::terminfo::TermInfo;
+
+use std::env;
+
+use super::color::Color;
+use super::{Attr, Rgb, TermStyle};
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+/// The number of colors a terminal can display.
+///
+/// Variants are ordered from the least to the most capable.
+pub enum ColorDepth {
+    /// No colors at all.
+    NoColor,
+    /// The 8 basic colors.
+    Ansi8,
+    /// The 8 basic colors and their bright variants.
+    Ansi16,
+    /// The xterm-256 palette.
+    Ansi256,
+    /// 24-bit [`Rgb`] colors, in addition to the xterm-256 palette.
+    TrueColor,
+}
+
+impl ColorDepth {
+    /// Get the color depth of the terminal described by `ti`.
+    ///
+    /// Truecolor support is detected with `COLORTERM` set to `truecolor`
+    /// or `24bit`. Otherwise, the terminfo `colors` number is used.
+    ///
+    /// Extended terminfo capabilities like `Tc` or `RGB` are not checked,
+    /// as they are not loaded by [`TermInfo`].
+    ///
+    /// [`TermInfo`]: ::term::terminfo::TermInfo
+    pub fn from_terminfo(ti: &TermInfo) -> Self {
+        let colorterm = env::var("COLORTERM").ok();
+        Self::_from_terminfo(Some(ti), colorterm.as_deref())
+    }
+
+    // from_terminfo() and from_env() with the value of COLORTERM passed in.
+    pub(crate) fn _from_terminfo(ti: Option<&TermInfo>, colorterm: Option<&str>) -> Self {
+        let ti = match (ti, colorterm) {
+            (None, _) => return ColorDepth::NoColor,
+            (Some(_), Some("truecolor")) | (Some(_), Some("24bit")) => return ColorDepth::TrueColor,
+            (Some(ti), _) => ti,
+        };
+
+        match ti.numbers.get("colors").map_or(0, |&n| n) {
+            n if n >= 256 => ColorDepth::Ansi256,
+            n if n >= 16 => ColorDepth::Ansi16,
+            n if n >= 8 => ColorDepth::Ansi8,
+            _ => ColorDepth::NoColor,
+        }
+    }
+
+    /// Get the color depth of the terminal set in the environment.
+    ///
+    /// [`ColorDepth::NoColor`] is returned if terminfo for the terminal
+    /// could not be loaded.
+    pub fn from_env() -> Self {
+        let colorterm = env::var("COLORTERM").ok();
+        Self::_from_terminfo(
+            TermInfo::from_env().ok().as_ref(),
+            colorterm.as_deref(),
+        )
+    }
+
+    /// The number of palette colors available with this depth.
+    pub fn palette_size(self) -> u32 {
+        match self {
+            ColorDepth::NoColor => 0,
+            ColorDepth::Ansi8 => 8,
+            ColorDepth::Ansi16 => 16,
+            ColorDepth::Ansi256 | ColorDepth::TrueColor => 256,
+        }
+    }
+}
+
+// xterm's default values for the 16 base colors
+const BASE16: [Rgb; 16] = [
+    Rgb(0x00, 0x00, 0x00),
+    Rgb(0xcd, 0x00, 0x00),
+    Rgb(0x00, 0xcd, 0x00),
+    Rgb(0xcd, 0xcd, 0x00),
+    Rgb(0x00, 0x00, 0xee),
+    Rgb(0xcd, 0x00, 0xcd),
+    Rgb(0x00, 0xcd, 0xcd),
+    Rgb(0xe5, 0xe5, 0xe5),
+    Rgb(0x7f, 0x7f, 0x7f),
+    Rgb(0xff, 0x00, 0x00),
+    Rgb(0x00, 0xff, 0x00),
+    Rgb(0xff, 0xff, 0x00),
+    Rgb(0x5c, 0x5c, 0xff),
+    Rgb(0xff, 0x00, 0xff),
+    Rgb(0x00, 0xff, 0xff),
+    Rgb(0xff, 0xff, 0xff),
+];
+
+const CUBE_LEVELS: [u8; 6] = [0x00, 0x5f, 0x87, 0xaf, 0xd7, 0xff];
+
+// The color xterm displays for a palette index by default.
+pub(crate) fn palette_rgb(color: Color) -> Rgb {
+    match color {
+        0..=15 => BASE16[color as usize],
+        16..=231 => {
+            let i = color - 16;
+            Rgb(
+                CUBE_LEVELS[(i / 36) as usize],
+                CUBE_LEVELS[(i / 6 % 6) as usize],
+                CUBE_LEVELS[(i % 6) as usize],
+            )
+        },
+        232..=255 => {
+            let level = 8 + (color - 232) as u8 * 10;
+            Rgb(level, level, level)
+        },
+        _ => BASE16[(color % 16) as usize],
+    }
+}
+
+// sRGB to CIELAB (D65 white point).
+fn lab(rgb: Rgb) -> (f64, f64, f64) {
+    fn linear(c: u8) -> f64 {
+        let c = f64::from(c) / 255.0;
+        if c <= 0.04045 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    }
+
+    fn f(t: f64) -> f64 {
+        if t > 216.0 / 24389.0 {
+            t.cbrt()
+        } else {
+            (24389.0 / 27.0 * t + 16.0) / 116.0
+        }
+    }
+
+    let Rgb(r, g, b) = rgb;
+    let (r, g, b) = (linear(r), linear(g), linear(b));
+
+    let x = (0.4124 * r + 0.3576 * g + 0.1805 * b) / 0.95047;
+    let y = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+    let z = (0.0193 * r + 0.1192 * g + 0.9505 * b) / 1.08883;
+
+    let (fx, fy, fz) = (f(x), f(y), f(z));
+    (116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz))
+}
+
+// Squared CIE76 color difference. Good enough to pick the nearest
+// palette entry, and way better than plain RGB distance.
+fn distance(rgb1: Rgb, rgb2: Rgb) -> f64 {
+    let (l1, a1, b1) = lab(rgb1);
+    let (l2, a2, b2) = lab(rgb2);
+    (l1 - l2).powi(2) + (a1 - a2).powi(2) + (b1 - b2).powi(2)
+}
+
+// The palette index in [from, to) displayed as the nearest color to rgb.
+fn nearest(rgb: Rgb, from: Color, to: Color) -> Color {
+    (from..to)
+        .map(|color| (color, distance(rgb, palette_rgb(color))))
+        .min_by(|c1, c2| c1.1.partial_cmp(&c2.1).expect("should never happen"))
+        .map_or(from, |(color, _)| color)
+}
+
+// The nearest palette index to rgb for a depth with a palette.
+fn nearest_for_depth(rgb: Rgb, depth: ColorDepth) -> Color {
+    match depth {
+        // The base 16 colors are often customized by users, so only
+        // the color cube and the grayscale ramp are considered.
+        ColorDepth::Ansi256 | ColorDepth::TrueColor => nearest(rgb, 16, 256),
+        _ => nearest(rgb, 0, depth.palette_size()),
+    }
+}
+
+/// Methods for fitting a [`TermStyle`] to what a terminal can display.
+impl TermStyle {
+    /// Convert colors set in [`TermStyle`] to colors displayable with `depth`.
+    ///
+    /// * With [`ColorDepth::TrueColor`], nothing changes.
+    /// * [`Rgb`] colors are mapped to the nearest xterm-256 color, if `depth`
+    ///   is [`ColorDepth::Ansi256`].
+    /// * Rgb and palette colors are mapped to the nearest of the base 16
+    ///   (or 8) colors, if `depth` is [`ColorDepth::Ansi16`] (or [`ColorDepth::Ansi8`]).
+    /// * All colors are unset, if `depth` is [`ColorDepth::NoColor`].
+    ///
+    /// Nearest colors are chosen by perceptual distance (CIELAB).
+    ///
+    /// # Examples
+    /// ``` rust
+    /// use term_string::{color, ColorDepth, TermStyle};
+    ///
+    /// let mut style = TermStyle::bold() + TermStyle::fg_rgb(0xff, 0x88, 0x00);
+    /// style.downsample(ColorDepth::Ansi256);
+    /// assert!(style.has_exact_fg(208));
+    /// style.downsample(ColorDepth::Ansi16);
+    /// assert!(style.has_exact_fg(color::RED));
+    /// style.downsample(ColorDepth::NoColor);
+    /// assert_eq!(style, TermStyle::bold());
+    /// ```
+    pub fn downsample(&mut self, depth: ColorDepth) {
+        if depth == ColorDepth::TrueColor {
+            return;
+        }
+
+        for &attr in &[Attr::ForegroundColor(0), Attr::BackgroundColor(0)] {
+            let color = match (self._rgb(attr), self._palette_color(attr)) {
+                _ if depth == ColorDepth::NoColor => None,
+                (Some(rgb), _) => Some(nearest_for_depth(rgb, depth)),
+                (None, Some(color)) if color >= depth.palette_size() => {
+                    Some(nearest_for_depth(palette_rgb(color), depth))
+                },
+                (None, color) => color,
+            };
+
+            self.unset_variant_attr(attr);
+            if let Some(color) = color {
+                self.add_attr(match attr {
+                    Attr::ForegroundColor(_) => Attr::ForegroundColor(color),
+                    _ => Attr::BackgroundColor(color),
+                });
+            }
+        }
+    }
+
+    chaining_fn!(TermStyle, downsample,
+                 pub fn with_downsampled(mut self, depth: ColorDepth) -> Self {
+                     self.downsample(depth);
+                     self
+                 }
+    );
+}
@@ -27,6 +27,7 @@ macro_rules! gen_idents {
                 m_op["with_ored" $t] = with_ored_ $t;
                 m_a_s["attr" $t] = $t _attr;
                 m_a_s["style" $t] = $t _style;
+                m_a_s["rgb" $t] = $t _rgb;
         )* }
     );
 }
@@ -37,8 +38,8 @@ macro_rules! gen_attr_fns {
             gen_fn_with_doc!(
                 concat!("Create a new [`TermStyle`] with [`Attr::", stringify!($v), "`] set.\n\n",
                 "This is equivalent to `TermStyle::from([`[`Attr::", stringify!($v), "`]`])`."),
-                pub fn $t() -> Self {
-                    Self::from([Attr::$v])
+                pub const fn $t() -> Self {
+                    Self::new()._with_attr(Attr::$v)
                 }
             );
         )*
@@ -63,8 +64,8 @@ macro_rules! gen_attr_fns {
 
                 chaining_fn!(
                     TermStyle, "add" $t,
-                    pub fn "with" $t(self) -> Self {
-                        self.with_attr(Attr::$v)
+                    pub const fn "with" $t(self) -> Self {
+                        self._with_attr(Attr::$v)
                     }
                 );
             )* }
@@ -90,8 +91,8 @@ macro_rules! gen_attr_fns {
             gen_fn_with_doc!(
                 concat!("Create a new [`TermStyle`] with [`Attr::", stringify!($v), "`]`(arg)` set.\n\n",
                 "This is equivalent to `TermStyle::from([`[`Attr::", stringify!($v), "`]`(arg) ])`."),
-                pub fn $t(arg: $arg_ty) -> Self {
-                    Self::from([Attr::$v(arg)])
+                pub const fn $t(arg: $arg_ty) -> Self {
+                    Self::new()._with_attr(Attr::$v(arg))
                 }
             );
         )*
@@ -122,8 +123,8 @@ macro_rules! gen_attr_fns {
 
                 chaining_fn!(
                     TermStyle, "add" $t,
-                    pub fn "with" $t(self, arg: $arg_ty) -> Self {
-                        self.with_attr(Attr::$v(arg))
+                    pub const fn "with" $t(self, arg: $arg_ty) -> Self {
+                        self._with_attr(Attr::$v(arg))
                     }
                 );
 
@@ -176,6 +177,103 @@ macro_rules! gen_attr_fns {
 
 }
 
+macro_rules! gen_rgb_fns {
+    ($([$t:ident, $v:ident]),*) => (
+        $(
+            gen_fn_with_doc!(
+                concat!("Create a new [`TermStyle`] with an [`Rgb`] color set in place of\n",
+                "[`Attr::", stringify!($v), "`]."),
+                pub const fn $t(r: u8, g: u8, b: u8) -> Self {
+                    Self::new()._with_rgb(Attr::$v(0), Rgb(r, g, b))
+                }
+            );
+        )*
+
+        m_has! { $(
+                gen_fn_with_doc!(
+                    concat!("Check if an [`Rgb`] color is set in place of [`Attr::", stringify!($v), "`]."),
+                    pub fn "has" $t(&self) -> bool {
+                        self._rgb(Attr::$v(0)).is_some()
+                    }
+                );
+
+                gen_fn_with_doc!(
+                    concat!("Check if `Rgb(r, g, b)` is set in place of [`Attr::", stringify!($v), "`]."),
+                    pub fn "has_exact" $t(&self, r: u8, g: u8, b: u8) -> bool {
+                        self.has_exact_rgb(Attr::$v(0), Rgb(r, g, b))
+                    }
+                );
+            )* }
+        m_op! { $(
+                gen_fn_with_doc!(
+                    concat!("Set/Add `Rgb(r, g, b)` to style, replacing [`Attr::", stringify!($v), "`]\n",
+                    "or any [`Rgb`] color already set in its place."),
+                    pub fn "add" $t(&mut self, r: u8, g: u8, b: u8) {
+                        self.add_rgb(Attr::$v(0), Rgb(r, g, b));
+                    }
+                );
+
+                chaining_fn!(
+                    TermStyle, "add" $t,
+                    pub const fn "with" $t(self, r: u8, g: u8, b: u8) -> Self {
+                        self._with_rgb(Attr::$v(0), Rgb(r, g, b))
+                    }
+                );
+
+                gen_fn_with_doc!(
+                    concat!("Set/Add `Rgb(r, g, b)` to style,\n",
+                    "if neither [`Attr::", stringify!($v), "`] nor an [`Rgb`] color in its place is already set."),
+                    pub fn "or" $t(&mut self, r: u8, g: u8, b: u8) {
+                        self.or_rgb(Attr::$v(0), Rgb(r, g, b));
+                    }
+                );
+
+                chaining_fn!(
+                    TermStyle, "or" $t,
+                    pub fn "with_ored" $t(mut self, r: u8, g: u8, b: u8) -> Self {
+                        self.or_rgb(Attr::$v(0), Rgb(r, g, b));
+                        self
+                    }
+                );
+            )* }
+        m_unset! { $(
+                gen_fn_with_doc!(
+                    concat!("Unset/Remove any [`Rgb`] color set in place of [`Attr::", stringify!($v), "`].\n",
+                            "A palette color set with [`Attr::", stringify!($v), "`] is kept."),
+                    pub fn "unset" $t(&mut self) {
+                        if let Some(rgb) = self._rgb(Attr::$v(0)) {
+                            self.unset_exact_rgb(Attr::$v(0), rgb);
+                        }
+                    }
+                );
+
+                chaining_fn!(
+                    TermStyle, "unset" $t,
+                    pub fn "without" $t(mut self) -> Self {
+                        self."unset" $t();
+                        self
+                    }
+                );
+
+                gen_fn_with_doc!(
+                    concat!("Unset/Remove `Rgb(r, g, b)` from style, if set in place of [`Attr::",
+                            stringify!($v), "`]."),
+                    pub fn "unset_exact" $t(&mut self, r: u8, g: u8, b: u8) {
+                        self.unset_exact_rgb(Attr::$v(0), Rgb(r, g, b));
+                    }
+                );
+
+                chaining_fn!(
+                    TermStyle, "unset_exact" $t,
+                    pub fn "without_exact" $t(mut self, r: u8, g: u8, b: u8) -> Self {
+                        self."unset_exact" $t(r, g, b);
+                        self
+                    }
+                );
+            )* }
+    );
+}
+
 macro_rules! gen_with_fn {
     (attr, $t:ident, $b:ident) => (
         chaining_fn!(TermStyle, $b,
@@ -206,9 +304,10 @@ macro_rules! gen_from_attr_fns {
                     concat!("Apply [`", stringify!("attr" $t), "()`] to all [`Attr`]s set in other.\n\n",
                     "[`", stringify!("attr" $t), "()`]:", " TermStyle::", stringify!("attr" $t)),
                     pub fn "style" $t<IS>(&mut self, other: IS) where IS: Into<Self> {
-                        other.into().attrs.iter()
-                            .filter_map(|&attr| attr)
+                        let other = other.into();
+                        other.iter()
                             .for_each(|attr| self."attr" $t(attr));
+                        other._rgbs().for_each(|(attr, rgb)| self."rgb" $t(attr, rgb));
                     }
                 );
         )* }
@@ -220,10 +319,11 @@ macro_rules! gen_from_attr_fns {
                     concat!("Apply [`", stringify!("attr" $t), "()`] to all [`Attr`]s set in other.\n\n",
                     "[`", stringify!("attr" $t), "()`]:", " TermStyle::", stringify!("attr" $t)),
                     pub fn "style" $t<IS>(&self, other: IS) -> bool where IS: Into<Self> {
-                        other.into().attrs.iter()
-                            .filter_map(|&attr| attr)
+                        let other = other.into();
+                        other.iter()
                             .map(|attr| self."attr" $t(attr))
                             .find(|&has| !has).is_none()
+                            && other._rgbs().all(|(attr, rgb)| self."rgb" $t(attr, rgb))
                     }
                 );
         )* }
@@ -13,21 +13,86 @@
 mod tests;
 #[macro_use]
 mod macros;
+mod depth;
+mod set;
+mod sgr;
+mod spec;
 
+pub use self::depth::ColorDepth;
+pub use self::set::Attrs;
 pub use term::{color, Attr};
 
 use std::borrow::Borrow;
-use std::mem;
+use std::fmt;
 use std::ops::{Add, AddAssign, BitOr, BitOrAssign, Sub, SubAssign};
 
 use self::color::Color;
 
-#[derive(Copy, Clone, Default, Debug)]
+#[derive(Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+/// A 24-bit (truecolor) color.
+///
+/// Unlike [`Color`], which is an index into the terminal's palette,
+/// [`Rgb`] describes the exact color to be displayed. It's only emitted
+/// as-is by terminals that support truecolor.
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 /// Styling info for [`TermString`].
 ///
-/// Internally, [`TermStyle`] has zero or more [`Attr`]s set.
+/// Internally, [`TermStyle`] has a flag bit for each [`Attr`] with no color
+/// data set (`bool` data is part of the flag), in addition to foreground and
+/// background slots, each holding a palette [`Color`], an [`Rgb`] color, or
+/// nothing. So styles are small and cheap to copy and compare, and the order
+/// attributes were set in doesn't matter.
+///
+/// Styles can be built in constant expressions, using [`new()`], the
+/// constructors, and the chaining `with_*` methods for single attributes.
+///
+/// [`new()`]: TermStyle::new
+///
+/// # Examples
+/// ``` rust
+/// use term_string::{color, TermStyle};
+///
+/// const BOLD_RED: TermStyle = TermStyle::new().with_bold().with_fg(color::RED);
+/// const THEME: [TermStyle; 2] = [BOLD_RED, TermStyle::underline(true).with_bg_rgb(0, 0, 0x80)];
+///
+/// assert_eq!(BOLD_RED, TermStyle::fg(color::RED) + TermStyle::bold());
+/// assert!(THEME[1].has_exact_bg_rgb(0, 0, 0x80));
+/// ```
+///
+/// # Comparison
+///
+/// Equality and hashing are consistent with [`eq_style()`]. So styles can
+/// be used as keys in maps and sets.
+///
+/// Styles are also ordered, so they can be sorted deterministically. The
+/// order doesn't depend on how styles were built, but is otherwise not
+/// meaningful, except that the default style is the smallest.
+///
+/// [`eq_style()`]: TermStyle::eq_style
+///
+/// ``` rust
+/// use std::collections::HashSet;
+/// use term_string::{color, TermStyle};
+///
+/// let mut styles = vec![
+///     TermStyle::fg(color::RED) + TermStyle::bold(),
+///     TermStyle::default(),
+///     TermStyle::bold() + TermStyle::fg(color::RED),
+/// ];
+///
+/// let set: HashSet<_> = styles.iter().collect();
+/// assert_eq!(set.len(), 2);
+///
+/// styles.sort();
+/// styles.dedup();
+/// assert_eq!(styles, [TermStyle::default(), TermStyle::bold() + TermStyle::fg(color::RED)]);
+/// ```
 pub struct TermStyle {
-    pub(crate) attrs: [Option<Attr>; 10],
+    flags: u16,
+    fg: ColorSlot,
+    bg: ColorSlot,
 }
 
 gen_idents!(
@@ -41,6 +106,8 @@ gen_idents!(
     standout,
     fg,
     bg,
+    fg_rgb,
+    bg_rgb,
     has_exact,
     has_variant,
     unset_exact,
@@ -90,7 +157,8 @@ impl TermStyle {
 /// pairs (enter capability mode, exit capability mode).
 ///
 /// There is no reason and no need to set any of those attributes with `false`
-/// here, as styles are fully reset between writes/prints. The API is still fully
+/// here, as text never inherits attributes from text written before it. Setting
+/// them with `false` has the same effect as not setting them. The API is still fully
 /// exposed to stay close and introduce no magic over what the [`term`] crate
 /// exposes ([`Attr`] is a re-export of [`term`]::Attr).
 ///
@@ -154,55 +222,215 @@ impl TermStyle {
     gen_attr_fns!([fg, ForegroundColor, Color], [bg, BackgroundColor, Color]);
 }
 
+/// Convenient methods for setting, unsetting, and checking 24-bit [`Rgb`]
+/// colors in a [`TermStyle`] variable.
+///
+/// (*`fg_rgb`*, *`bg_rgb`*)
+///
+/// An [`Rgb`] color occupies the same slot as the [`Attr`] variant of
+/// the same ground. So setting `fg_rgb` replaces [`Attr::ForegroundColor`]
+/// and vice versa. And methods like [`has_fg()`], [`or_fg()`] and
+/// [`unset_fg()`] take [`Rgb`] foreground colors into account.
+///
+/// [`has_fg()`]: TermStyle::has_fg
+/// [`or_fg()`]: TermStyle::or_fg
+/// [`unset_fg()`]: TermStyle::unset_fg
+///
+/// # Examples
+/// ``` rust
+/// use term_string::{TermStyle, color};
+///
+/// let mut style = TermStyle::fg_rgb(0xff, 0x88, 0x00);
+/// assert!(style.has_fg());
+/// assert!(style.has_fg_rgb());
+/// assert!(style.has_exact_fg_rgb(0xff, 0x88, 0x00));
+/// // ==========
+/// // fg is already set
+/// style.or_fg(color::BLUE);
+/// assert!(!style.has_exact_fg(color::BLUE));
+/// // ==========
+/// // palette colors replace rgb colors
+/// style.add_fg(color::BLUE);
+/// assert!(style.has_exact_fg(color::BLUE));
+/// assert!(!style.has_fg_rgb());
+/// // ==========
+/// style.add_bg_rgb(0x00, 0x00, 0x80);
+/// style.unset_bg();
+/// assert!(!style.has_bg());
+/// ```
+///
+impl TermStyle {
+    gen_rgb_fns!([fg_rgb, ForegroundColor], [bg_rgb, BackgroundColor]);
+}
+
+// Flag bits of the attrs with no color data. The attrs with bool data
+// have a bit for each value, and at most one of the two is set.
+const BOLD: u16 = 1;
+const DIM: u16 = 1 << 1;
+const BLINK: u16 = 1 << 2;
+const REVERSE: u16 = 1 << 3;
+const SECURE: u16 = 1 << 4;
+const ITALIC: u16 = 1 << 5;
+const NO_ITALIC: u16 = 1 << 6;
+const UNDERLINE: u16 = 1 << 7;
+const NO_UNDERLINE: u16 = 1 << 8;
+const STANDOUT: u16 = 1 << 9;
+const NO_STANDOUT: u16 = 1 << 10;
+
+const FLAG_ATTRS: [(u16, Attr); 11] = [
+    (BOLD, Attr::Bold),
+    (DIM, Attr::Dim),
+    (BLINK, Attr::Blink),
+    (REVERSE, Attr::Reverse),
+    (SECURE, Attr::Secure),
+    (ITALIC, Attr::Italic(true)),
+    (NO_ITALIC, Attr::Italic(false)),
+    (UNDERLINE, Attr::Underline(true)),
+    (NO_UNDERLINE, Attr::Underline(false)),
+    (STANDOUT, Attr::Standout(true)),
+    (NO_STANDOUT, Attr::Standout(false)),
+];
+
+// The flag bit of attr. Zero for color attrs.
+const fn flag(attr: Attr) -> u16 {
+    match attr {
+        Attr::Bold => BOLD,
+        Attr::Dim => DIM,
+        Attr::Blink => BLINK,
+        Attr::Reverse => REVERSE,
+        Attr::Secure => SECURE,
+        Attr::Italic(true) => ITALIC,
+        Attr::Italic(false) => NO_ITALIC,
+        Attr::Underline(true) => UNDERLINE,
+        Attr::Underline(false) => NO_UNDERLINE,
+        Attr::Standout(true) => STANDOUT,
+        Attr::Standout(false) => NO_STANDOUT,
+        Attr::ForegroundColor(_) | Attr::BackgroundColor(_) => 0,
+    }
+}
+
+// The flag bits of attr's variant, whatever its data is.
+const fn variant_flags(attr: Attr) -> u16 {
+    match attr {
+        Attr::Italic(_) => ITALIC | NO_ITALIC,
+        Attr::Underline(_) => UNDERLINE | NO_UNDERLINE,
+        Attr::Standout(_) => STANDOUT | NO_STANDOUT,
+        _ => flag(attr),
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+// What a foreground or background color is set to.
+enum ColorSlot {
+    Unset,
+    Palette(Color),
+    Rgb(Rgb),
+}
+
 // Internal: use carefully
 impl TermStyle {
-    // Append attr at the position of first None, regardless
-    // of what the array contents are.
-    fn _append_attr(&mut self, attr: Attr) {
-        let first_none = self
-            .attrs
-            .iter()
-            .position(|&g_attr| g_attr == None)
-            .expect("should never happen");
-
-        self.attrs[first_none] = Some(attr);
-    }
-
-    // Find the position of an attr match if any.
-    // Will match by variant if exact=false
-    fn _attr_match_pos(&self, attr: Attr, exact: bool) -> Option<usize> {
-        if exact {
-            self.attrs.iter().position(|&g_attr| g_attr == Some(attr))
-        } else {
-            self.attrs.iter().position(|&g_attr| match g_attr {
-                None => false,
-                Some(g_attr) => mem::discriminant(&g_attr) == mem::discriminant(&attr),
-            })
+    // The color slot of attr's variant, if attr is a color attr.
+    fn _slot_mut(&mut self, attr: Attr) -> Option<&mut ColorSlot> {
+        match attr {
+            Attr::ForegroundColor(_) => Some(&mut self.fg),
+            Attr::BackgroundColor(_) => Some(&mut self.bg),
+            _ => None,
+        }
+    }
+
+    fn _slot(&self, attr: Attr) -> ColorSlot {
+        match attr {
+            Attr::ForegroundColor(_) => self.fg,
+            Attr::BackgroundColor(_) => self.bg,
+            _ => ColorSlot::Unset,
         }
     }
 
-    // If no variant match is found, append attr
-    // If a match is found, replace the match if
-    // replace=true, otherwise, don't do anything.
-    fn _add_attr(&mut self, attr: Attr, replace: bool) {
-        let variant_match_pos = self._attr_match_pos(attr, false);
+    // The chaining equivalent of add_attr(), usable in constants.
+    const fn _with_attr(mut self, attr: Attr) -> Self {
+        match attr {
+            Attr::ForegroundColor(color) => self.fg = ColorSlot::Palette(color),
+            Attr::BackgroundColor(color) => self.bg = ColorSlot::Palette(color),
+            _ => self.flags = (self.flags & !variant_flags(attr)) | flag(attr),
+        }
+        self
+    }
 
-        match (variant_match_pos, replace) {
-            (None, _) => self._append_attr(attr),
-            (Some(pos), true) => self.attrs[pos] = Some(attr),
+    // The chaining equivalent of add_rgb(), usable in constants.
+    const fn _with_rgb(mut self, attr: Attr, rgb: Rgb) -> Self {
+        match attr {
+            Attr::ForegroundColor(_) => self.fg = ColorSlot::Rgb(rgb),
+            Attr::BackgroundColor(_) => self.bg = ColorSlot::Rgb(rgb),
             _ => (),
-        };
+        }
+        self
+    }
+
+    // The rgb color set for the variant of attr, if any.
+    pub(crate) fn _rgb(&self, attr: Attr) -> Option<Rgb> {
+        match self._slot(attr) {
+            ColorSlot::Rgb(rgb) => Some(rgb),
+            _ => None,
+        }
     }
 
-    // Remove an attr if a match is found (replace with None).
-    // Will match by variant if exact=false
-    fn _remove_attr(&mut self, attr: Attr, exact: bool) {
-        let match_pos = self._attr_match_pos(attr, exact);
+    // The palette color set with the color variant of attr, if any.
+    pub(crate) fn _palette_color(&self, attr: Attr) -> Option<Color> {
+        match self._slot(attr) {
+            ColorSlot::Palette(color) => Some(color),
+            _ => None,
+        }
+    }
+
+    // A style with only the background color of this one, if any.
+    pub(crate) fn _bg_style(&self) -> Self {
+        Self {
+            bg: self.bg,
+            ..Self::new()
+        }
+    }
+
+    // Set rgb colors paired with a color attr of the same variant.
+    pub(crate) fn _rgbs(&self) -> impl Iterator<Item = (Attr, Rgb)> {
+        let fg = self
+            ._rgb(Attr::ForegroundColor(0))
+            .map(|rgb| (Attr::ForegroundColor(0), rgb));
+        let bg = self
+            ._rgb(Attr::BackgroundColor(0))
+            .map(|rgb| (Attr::BackgroundColor(0), rgb));
+        fg.into_iter().chain(bg)
+    }
+
+    // Counterparts of the methods taking Attr for the rgb colors of a
+    // style. attr only selects the variant here.
+    pub(crate) fn has_exact_rgb(&self, attr: Attr, rgb: Rgb) -> bool {
+        self._rgb(attr) == Some(rgb)
+    }
+
+    fn has_variant_rgb(&self, attr: Attr, _: Rgb) -> bool {
+        self.has_variant_attr(attr)
+    }
 
-        if let Some(pos) = match_pos {
-            self.attrs[pos] = None;
+    fn unset_exact_rgb(&mut self, attr: Attr, rgb: Rgb) {
+        if self.has_exact_rgb(attr, rgb) {
+            self.unset_variant_attr(attr);
         }
     }
+
+    fn unset_variant_rgb(&mut self, attr: Attr, _: Rgb) {
+        self.unset_variant_attr(attr);
+    }
+
+    fn or_rgb(&mut self, attr: Attr, rgb: Rgb) {
+        if !self.has_variant_attr(attr) {
+            self.add_rgb(attr, rgb);
+        }
+    }
+
+    // A palette color in the same variant is replaced.
+    fn add_rgb(&mut self, attr: Attr, rgb: Rgb) {
+        *self = self._with_rgb(attr, rgb);
+    }
 }
 
 /// Methods that take [`Attr`] as an argument.
@@ -221,7 +449,12 @@ impl TermStyle {
     /// assert!(!style.has_exact_attr(Attr::Underline(false)));
     /// ```
     pub fn has_exact_attr(&self, attr: Attr) -> bool {
-        self._attr_match_pos(attr, true).is_some()
+        match attr {
+            Attr::ForegroundColor(color) | Attr::BackgroundColor(color) => {
+                self._palette_color(attr) == Some(color)
+            },
+            _ => self.flags & flag(attr) != 0,
+        }
     }
 
     /// [`TermStyle`] has attr set. Variant is referring to
@@ -237,7 +470,7 @@ impl TermStyle {
     /// assert!(style.has_variant_attr(Attr::Underline(false)));
     /// ```
     pub fn has_variant_attr(&self, attr: Attr) -> bool {
-        self._attr_match_pos(attr, false).is_some()
+        self._slot(attr) != ColorSlot::Unset || self.flags & variant_flags(attr) != 0
     }
 
     /// Unset/Remove the exact [`Attr`] from [`TermStyle`].
@@ -257,7 +490,9 @@ impl TermStyle {
     /// assert_eq!(style, TermStyle::default());
     /// ```
     pub fn unset_exact_attr(&mut self, attr: Attr) {
-        self._remove_attr(attr, true);
+        if self.has_exact_attr(attr) {
+            self.unset_variant_attr(attr);
+        }
     }
 
     /// Unset/Remove the variant [`Attr`] from [`TermStyle`].
@@ -273,7 +508,10 @@ impl TermStyle {
     /// assert_eq!(style, TermStyle::default());
     /// ```
     pub fn unset_variant_attr(&mut self, attr: Attr) {
-        self._remove_attr(attr, false);
+        self.flags &= !variant_flags(attr);
+        if let Some(slot) = self._slot_mut(attr) {
+            *slot = ColorSlot::Unset;
+        }
     }
 
     /// Set/Add attr to [`TermStyle`], unless the same variant
@@ -295,8 +533,10 @@ impl TermStyle {
     /// assert!(style.has_exact_bg(color::RED));
     /// ```
     pub fn or_attr(&mut self, attr: Attr) {
-        // replace = false
-        self._add_attr(attr, false);
+        // An rgb color counts as the same variant
+        if !self.has_variant_attr(attr) {
+            self.add_attr(attr);
+        }
     }
 
     /// Set/Add attr to [`TermStyle`], overriding the same variant
@@ -318,13 +558,18 @@ impl TermStyle {
     /// assert!(style.has_exact_bg(color::GREEN));
     /// ```
     pub fn add_attr(&mut self, attr: Attr) {
-        // replace = true
-        self._add_attr(attr, true);
+        *self = self._with_attr(attr);
     }
 
     gen_with_fn!(attr, without_exact_attr, unset_exact_attr);
     gen_with_fn!(attr, without_variant_attr, unset_variant_attr);
-    gen_with_fn!(attr, with_attr, add_attr);
+
+    chaining_fn!(TermStyle, add_attr,
+                 pub const fn with_attr(self, attr: Attr) -> Self {
+                     self._with_attr(attr)
+                 }
+    );
+
     gen_with_fn!(attr, with_ored_attr, or_attr);
 }
 
@@ -365,14 +610,28 @@ impl TermStyle {
 
 /// Other methods
 impl TermStyle {
+    /// Create a new [`TermStyle`] with no attributes set.
+    ///
+    /// This is the same as [`TermStyle::default()`], but usable in
+    /// constant expressions.
+    ///
+    /// [`TermStyle::default()`]: TermStyle::default
+    pub const fn new() -> Self {
+        Self {
+            flags: 0,
+            fg: ColorSlot::Unset,
+            bg: ColorSlot::Unset,
+        }
+    }
+
     /// Resets style to default.
     pub fn reset(&mut self) {
         *self = Self::default();
     }
 
     /// Checks if both styles have the same exact attributes set.
-    /// This is used for implementing [`PartialEq`], so you should
-    /// probably use that instead.
+    /// This is the same as `==`, but `other` can be anything
+    /// converting into a [`TermStyle`].
     ///
     /// # Examples
     ///
@@ -415,9 +674,19 @@ impl TermStyle {
     }
 }
 
-impl PartialEq for TermStyle {
-    fn eq(&self, other: &Self) -> bool {
-        self.eq_style(*other)
+impl Default for TermStyle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for TermStyle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TermStyle")
+            .field("attrs", &self.iter().collect::<Vec<_>>())
+            .field("rgb_fg", &self._rgb(Attr::ForegroundColor(0)))
+            .field("rgb_bg", &self._rgb(Attr::BackgroundColor(0)))
+            .finish()
     }
 }
 
@@ -0,0 +1,396 @@
+/*
+    This file is a part of term-string.
+
+    Copyright (C) 2018 Mohammad AlSaleh <CE.Mohammad.AlSaleh at gmail.com>
+    https://github.com/rust-alt/term-string
+
+    This Source Code Form is subject to the terms of the Mozilla Public
+    License, v. 2.0. If a copy of the MPL was not distributed with this
+    file, You can obtain one at <http://mozilla.org/MPL/2.0/>.
+*/
+
+use std::iter::FromIterator;
+use std::ops::{BitAnd, BitAndAssign, BitXor, BitXorAssign};
+
+use super::{Attr, TermStyle, FLAG_ATTRS};
+
+#[derive(Clone, Debug)]
+/// An iterator over the [`Attr`]s set in a [`TermStyle`].
+///
+/// This is returned by [`TermStyle::iter()`].
+///
+/// [`TermStyle::iter()`]: TermStyle::iter
+pub struct Attrs {
+    style: TermStyle,
+    // Position in FLAG_ATTRS, followed by the fg and bg colors
+    pos: usize,
+}
+
+impl Iterator for Attrs {
+    type Item = Attr;
+
+    fn next(&mut self) -> Option<Attr> {
+        while self.pos < FLAG_ATTRS.len() + 2 {
+            let pos = self.pos;
+            self.pos += 1;
+
+            let attr = match FLAG_ATTRS.get(pos) {
+                Some(&(_, attr)) if self.style.has_exact_attr(attr) => Some(attr),
+                Some(_) => None,
+                None if pos == FLAG_ATTRS.len() => self
+                    .style
+                    ._palette_color(Attr::ForegroundColor(0))
+                    .map(Attr::ForegroundColor),
+                None => self
+                    .style
+                    ._palette_color(Attr::BackgroundColor(0))
+                    .map(Attr::BackgroundColor),
+            };
+
+            if attr.is_some() {
+                return attr;
+            }
+        }
+
+        None
+    }
+}
+
+/// Methods for treating [`TermStyle`] as a set of attributes.
+///
+/// Like with the other methods, the exact flavors take the data of
+/// attributes into account, while the variant flavors don't.
+///
+/// [`Rgb`] colors are treated like [`Attr::ForegroundColor`] and
+/// [`Attr::BackgroundColor`] here. But since they are not [`Attr`]s,
+/// they are not returned by [`iter()`].
+///
+/// [`Rgb`]: ::Rgb
+/// [`iter()`]: TermStyle::iter
+///
+/// # Examples
+/// ``` rust
+/// use term_string::{color, TermStyle};
+///
+/// let base = TermStyle::bold() + TermStyle::fg(color::WHITE);
+/// let theme = base + TermStyle::fg(color::RED) + TermStyle::underline(true);
+///
+/// // What the theme actually changed
+/// assert_eq!(theme.difference(base), TermStyle::fg(color::RED) + TermStyle::underline(true));
+/// // What the theme added
+/// assert_eq!(theme.variant_difference(base), TermStyle::underline(true));
+/// ```
+impl TermStyle {
+    /// Return an iterator over the [`Attr`]s set in [`TermStyle`], in a
+    /// fixed order.
+    ///
+    /// **This is lossy: [`Rgb`] colors are not [`Attr`]s, so they are
+    /// skipped.** Collecting the iterator back into a [`TermStyle`] drops
+    /// them, and it can yield fewer items than [`len()`] counts.
+    ///
+    /// [`Rgb`]: ::Rgb
+    /// [`len()`]: TermStyle::len
+    ///
+    /// # Examples
+    /// ``` rust
+    /// use term_string::{color, Attr, TermStyle};
+    ///
+    /// let style = TermStyle::fg(color::RED) + TermStyle::bold() + TermStyle::bg_rgb(0, 0, 0x80);
+    /// let attrs: Vec<_> = style.iter().collect();
+    /// assert_eq!(attrs, [Attr::Bold, Attr::ForegroundColor(color::RED)]);
+    /// ```
+    pub fn iter(&self) -> Attrs {
+        Attrs {
+            style: *self,
+            pos: 0,
+        }
+    }
+
+    /// Return the number of attributes set in [`TermStyle`], including
+    /// [`Rgb`] colors, which [`iter()`] skips.
+    ///
+    /// [`Rgb`]: ::Rgb
+    /// [`iter()`]: TermStyle::iter
+    ///
+    /// # Examples
+    /// ``` rust
+    /// # use term_string::TermStyle;
+    /// let style = TermStyle::bold() + TermStyle::italic(false) + TermStyle::fg_rgb(0xff, 0x88, 0x00);
+    /// assert_eq!(style.len(), 3);
+    /// assert_eq!(style.iter().count(), 2);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.iter().count() + self._rgbs().count()
+    }
+
+    /// Check if no attributes are set in [`TermStyle`].
+    ///
+    /// # Examples
+    /// ``` rust
+    /// # use term_string::TermStyle;
+    /// assert!(TermStyle::default().is_empty());
+    /// assert!(!TermStyle::bg_rgb(0, 0, 0).is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        *self == Self::new()
+    }
+
+    /// Return the attributes set in both styles.
+    ///
+    /// This is what `&` does.
+    ///
+    /// # Examples
+    /// ``` rust
+    /// # use term_string::TermStyle;
+    /// let st1 = TermStyle::bold() + TermStyle::underline(true);
+    /// let st2 = TermStyle::bold() + TermStyle::underline(false);
+    /// assert_eq!(st1.intersection(st2), TermStyle::bold());
+    /// ```
+    pub fn intersection<IS>(&self, other: IS) -> Self
+    where
+        IS: Into<Self>,
+    {
+        // Remove what's not in other
+        self.without_exact_style(self.difference(other))
+    }
+
+    /// Return the attributes set in [`TermStyle`], with their variants
+    /// set in `other`.
+    ///
+    /// # Examples
+    /// ``` rust
+    /// # use term_string::TermStyle;
+    /// let st1 = TermStyle::bold() + TermStyle::underline(true);
+    /// let st2 = TermStyle::underline(false);
+    /// assert_eq!(st1.variant_intersection(st2), TermStyle::underline(true));
+    /// ```
+    pub fn variant_intersection<IS>(&self, other: IS) -> Self
+    where
+        IS: Into<Self>,
+    {
+        self.without_exact_style(self.variant_difference(other))
+    }
+
+    /// Return the attributes set in [`TermStyle`], but not in `other`.
+    ///
+    /// This is the same as [`without_exact_style()`], and what `-` does.
+    ///
+    /// [`without_exact_style()`]: TermStyle::without_exact_style
+    ///
+    /// # Examples
+    /// ``` rust
+    /// # use term_string::TermStyle;
+    /// let st1 = TermStyle::bold() + TermStyle::underline(true);
+    /// let st2 = TermStyle::bold() + TermStyle::underline(false);
+    /// assert_eq!(st1.difference(st2), TermStyle::underline(true));
+    /// ```
+    pub fn difference<IS>(&self, other: IS) -> Self
+    where
+        IS: Into<Self>,
+    {
+        self.without_exact_style(other)
+    }
+
+    /// Return the attributes set in [`TermStyle`], with their variants
+    /// not set in `other`.
+    ///
+    /// This is the same as [`without_variant_style()`].
+    ///
+    /// [`without_variant_style()`]: TermStyle::without_variant_style
+    ///
+    /// # Examples
+    /// ``` rust
+    /// # use term_string::TermStyle;
+    /// let st1 = TermStyle::bold() + TermStyle::underline(true);
+    /// let st2 = TermStyle::underline(false);
+    /// assert_eq!(st1.variant_difference(st2), TermStyle::bold());
+    /// ```
+    pub fn variant_difference<IS>(&self, other: IS) -> Self
+    where
+        IS: Into<Self>,
+    {
+        self.without_variant_style(other)
+    }
+
+    /// Return the attributes set in one of the styles, but not in both.
+    ///
+    /// Only one value of a variant can be set. So, if the styles have
+    /// different values of the same variant, the one in [`TermStyle`]
+    /// is kept.
+    ///
+    /// This is what `^` does.
+    ///
+    /// # Examples
+    /// ``` rust
+    /// # use term_string::TermStyle;
+    /// let st1 = TermStyle::bold() + TermStyle::underline(true);
+    /// let st2 = TermStyle::bold() + TermStyle::underline(false) + TermStyle::dim();
+    /// assert_eq!(st1.symmetric_difference(st2), TermStyle::underline(true) + TermStyle::dim());
+    /// ```
+    pub fn symmetric_difference<IS>(&self, other: IS) -> Self
+    where
+        IS: Into<Self>,
+    {
+        let other = other.into();
+        self.difference(other) | other.difference(*self)
+    }
+
+    /// Return the attributes with their variants set in one of the styles,
+    /// but not in both.
+    ///
+    /// # Examples
+    /// ``` rust
+    /// # use term_string::TermStyle;
+    /// let st1 = TermStyle::bold() + TermStyle::underline(true);
+    /// let st2 = TermStyle::bold() + TermStyle::underline(false) + TermStyle::dim();
+    /// assert_eq!(st1.variant_symmetric_difference(st2), TermStyle::dim());
+    /// ```
+    pub fn variant_symmetric_difference<IS>(&self, other: IS) -> Self
+    where
+        IS: Into<Self>,
+    {
+        let other = other.into();
+        self.variant_difference(other) | other.variant_difference(*self)
+    }
+
+    /// Check if all attributes set in [`TermStyle`] are set in `other`.
+    ///
+    /// This is the same as `other.has_exact_style(style)`.
+    ///
+    /// # Examples
+    /// ``` rust
+    /// # use term_string::TermStyle;
+    /// let style = TermStyle::underline(true);
+    /// assert!(style.is_subset_of(style + TermStyle::bold()));
+    /// assert!(!style.is_subset_of(TermStyle::underline(false)));
+    /// ```
+    pub fn is_subset_of<IS>(&self, other: IS) -> bool
+    where
+        IS: Into<Self>,
+    {
+        other.into().has_exact_style(*self)
+    }
+
+    /// Check if the variants of all attributes set in [`TermStyle`] are
+    /// set in `other`.
+    ///
+    /// This is the same as `other.has_variant_style(style)`.
+    ///
+    /// # Examples
+    /// ``` rust
+    /// # use term_string::TermStyle;
+    /// let style = TermStyle::underline(true);
+    /// assert!(style.is_variant_subset_of(TermStyle::underline(false)));
+    /// assert!(!style.is_variant_subset_of(TermStyle::bold()));
+    /// ```
+    pub fn is_variant_subset_of<IS>(&self, other: IS) -> bool
+    where
+        IS: Into<Self>,
+    {
+        other.into().has_variant_style(*self)
+    }
+}
+
+impl IntoIterator for TermStyle {
+    type Item = Attr;
+    type IntoIter = Attrs;
+
+    fn into_iter(self) -> Attrs {
+        self.iter()
+    }
+}
+
+impl IntoIterator for &TermStyle {
+    type Item = Attr;
+    type IntoIter = Attrs;
+
+    fn into_iter(self) -> Attrs {
+        self.iter()
+    }
+}
+
+/// Collect [`Attr`]s into a [`TermStyle`], as with [`add_attr()`].
+///
+/// **Collecting [`iter()`] is lossy: [`Rgb`] colors are not [`Attr`]s,
+/// so they are not carried over.**
+///
+/// [`add_attr()`]: TermStyle::add_attr
+/// [`iter()`]: TermStyle::iter
+/// [`Rgb`]: ::Rgb
+///
+/// # Examples
+/// ``` rust
+/// use term_string::{Attr, TermStyle};
+///
+/// let style = TermStyle::bold() + TermStyle::underline(true) + TermStyle::dim();
+/// let no_dim: TermStyle = style.iter().filter(|&attr| attr != Attr::Dim).collect();
+/// assert_eq!(no_dim, TermStyle::bold() + TermStyle::underline(true));
+/// ```
+impl FromIterator<Attr> for TermStyle {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = Attr>,
+    {
+        let mut style = Self::new();
+        style.extend(iter);
+        style
+    }
+}
+
+/// Extend [`TermStyle`] with [`Attr`]s, as with [`add_attr()`].
+///
+/// [`add_attr()`]: TermStyle::add_attr
+impl Extend<Attr> for TermStyle {
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = Attr>,
+    {
+        for attr in iter {
+            self.add_attr(attr);
+        }
+    }
+}
+
+/// Check out [`intersection()`].
+///
+/// [`intersection()`]: TermStyle::intersection
+impl<IS> BitAnd<IS> for TermStyle
+where
+    IS: Into<Self>,
+{
+    type Output = Self;
+    fn bitand(self, other: IS) -> Self {
+        self.intersection(other)
+    }
+}
+
+impl<IS> BitAndAssign<IS> for TermStyle
+where
+    IS: Into<Self>,
+{
+    fn bitand_assign(&mut self, other: IS) {
+        *self = self.intersection(other);
+    }
+}
+
+/// Check out [`symmetric_difference()`].
+///
+/// [`symmetric_difference()`]: TermStyle::symmetric_difference
+impl<IS> BitXor<IS> for TermStyle
+where
+    IS: Into<Self>,
+{
+    type Output = Self;
+    fn bitxor(self, other: IS) -> Self {
+        self.symmetric_difference(other)
+    }
+}
+
+impl<IS> BitXorAssign<IS> for TermStyle
+where
+    IS: Into<Self>,
+{
+    fn bitxor_assign(&mut self, other: IS) {
+        *self = self.symmetric_difference(other);
+    }
+}
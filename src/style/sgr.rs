@@ -0,0 +1,321 @@
+/*
+    This file is a part of term-string.
+
+    Copyright (C) 2018 Mohammad AlSaleh <CE.Mohammad.AlSaleh at gmail.com>
+    https://github.com/rust-alt/term-string
+
+    This Source Code Form is subject to the terms of the Mozilla Public
+    License, v. 2.0. If a copy of the MPL was not distributed with this
+    file, You can obtain one at <http://mozilla.org/MPL/2.0/>.
+*/
+
+use error::{Error, Result};
+
+use super::color::Color;
+use super::{Attr, Rgb, TermStyle};
+
+// Color variants, with their base, bright base, and off SGR codes.
+const COLORS: [(Attr, u32, u32, u32); 2] = [
+    (Attr::ForegroundColor(0), 30, 90, 39),
+    (Attr::BackgroundColor(0), 40, 100, 49),
+];
+
+// A check for a flag being on, with its on and off SGR codes.
+type Flag = (fn(&TermStyle) -> bool, u32, u32);
+
+// Either a palette color, or an rgb one.
+enum SgrColor {
+    Palette(Color),
+    Rgb(Rgb),
+}
+
+// Parse the extended color parameters following 38 or 48.
+fn extended_color<I>(params: &mut I) -> Option<SgrColor>
+where
+    I: Iterator<Item = Option<u32>>,
+{
+    let mut next_u8 = || match params.next() {
+        Some(Some(n)) if n < 256 => Some(n as u8),
+        _ => None,
+    };
+
+    match next_u8()? {
+        5 => next_u8().map(|n| SgrColor::Palette(Color::from(n))),
+        2 => match (next_u8(), next_u8(), next_u8()) {
+            (Some(r), Some(g), Some(b)) => Some(SgrColor::Rgb(Rgb(r, g, b))),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Methods for converting [`TermStyle`] from and to SGR (Select Graphic
+/// Rendition) parameter strings, like the ones found in `GREP_COLORS`,
+/// `LS_COLORS`, or in `ESC [ ... m` sequences.
+///
+/// # Mapping
+///
+/// | [`Attr`]                       | SGR on           | SGR off      |
+/// |:------------------------------:|:----------------:|:------------:|
+/// | `Bold`                         | `1`              | `22`         |
+/// | `Dim`                          | `2`              | `22`         |
+/// | `Italic(true)`                 | `3`              | `23`         |
+/// | `Underline(true)`              | `4`              | `24`         |
+/// | `Blink`                        | `5` (or `6`)     | `25`         |
+/// | `Reverse`                      | `7`              | `27`         |
+/// | `Secure`                       | `8`              | `28`         |
+/// | `ForegroundColor(0..=7)`       | `30`-`37`        | `39`         |
+/// | `ForegroundColor(8..=15)`      | `90`-`97`        | `39`         |
+/// | `ForegroundColor(n)`           | `38;5;n`         | `39`         |
+/// | `BackgroundColor(0..=7)`       | `40`-`47`        | `49`         |
+/// | `BackgroundColor(8..=15)`      | `100`-`107`      | `49`         |
+/// | `BackgroundColor(n)`           | `48;5;n`         | `49`         |
+///
+/// [`Rgb`] colors map to `38;2;r;g;b` and `48;2;r;g;b`.
+///
+/// `Italic(false)`, `Underline(false)` and `Standout(false)` map to
+/// the off codes `23`, `24` and `27`. When parsing, off codes unset
+/// their attributes, so these are read back as unset. And since there
+/// is no SGR code for standout, `Standout(true)` is written as reverse
+/// (`7`), which is what most terminals use for it. Code `0` (or an empty parameter)
+/// resets the style parsed so far.
+impl TermStyle {
+    /// Parse a [`TermStyle`] from SGR parameters separated by `;`.
+    ///
+    /// Unknown or malformed codes are an error. Use [`from_sgr_lossy()`]
+    /// to skip them instead.
+    ///
+    /// [`from_sgr_lossy()`]: TermStyle::from_sgr_lossy
+    ///
+    /// # Examples
+    /// ``` rust
+    /// use term_string::{color, TermStyle};
+    ///
+    /// let style = TermStyle::from_sgr("01;31;44").unwrap();
+    /// assert_eq!(style, TermStyle::bold() + TermStyle::fg(color::RED) + TermStyle::bg(color::BLUE));
+    ///
+    /// let style = TermStyle::from_sgr("38;2;255;136;0").unwrap();
+    /// assert_eq!(style, TermStyle::fg_rgb(0xff, 0x88, 0x00));
+    ///
+    /// assert!(TermStyle::from_sgr("1;53").is_err());
+    /// assert!(TermStyle::from_sgr("38;5").is_err());
+    /// ```
+    pub fn from_sgr(sgr: &str) -> Result<Self> {
+        let mut style = Self::default();
+        style.apply_sgr(sgr, true)?;
+        Ok(style)
+    }
+
+    /// Parse a [`TermStyle`] from SGR parameters separated by `;`,
+    /// skipping unknown or malformed codes.
+    ///
+    /// # Examples
+    /// ``` rust
+    /// use term_string::TermStyle;
+    ///
+    /// let style = TermStyle::from_sgr_lossy("1;53;x;4");
+    /// assert_eq!(style, TermStyle::bold() + TermStyle::underline(true));
+    /// ```
+    pub fn from_sgr_lossy(sgr: &str) -> Self {
+        let mut style = Self::default();
+        let _ = style.apply_sgr(sgr, false);
+        style
+    }
+
+    /// Convert [`TermStyle`] to SGR parameters separated by `;`.
+    ///
+    /// An empty string is returned for a default [`TermStyle`].
+    ///
+    /// # Examples
+    /// ``` rust
+    /// use term_string::{color, TermStyle};
+    ///
+    /// let style = TermStyle::bg(color::BRIGHT_BLUE) + TermStyle::bold() + TermStyle::fg(208);
+    /// assert_eq!(style.to_sgr(), "1;38;5;208;104");
+    /// assert_eq!(TermStyle::from_sgr(&style.to_sgr()).unwrap(), style);
+    /// ```
+    pub fn to_sgr(&self) -> String {
+        let mut codes = Vec::with_capacity(8);
+
+        let flags = [
+            (Attr::Bold, 1),
+            (Attr::Dim, 2),
+            (Attr::Italic(true), 3),
+            (Attr::Italic(false), 23),
+            (Attr::Underline(true), 4),
+            (Attr::Underline(false), 24),
+            (Attr::Blink, 5),
+            (Attr::Reverse, 7),
+            (Attr::Standout(true), 7),
+            (Attr::Standout(false), 27),
+            (Attr::Secure, 8),
+        ];
+
+        for &(attr, code) in flags.iter() {
+            let code = code.to_string();
+            // Reverse and Standout(true) share the same code
+            if self.has_exact_attr(attr) && !codes.contains(&code) {
+                codes.push(code);
+            }
+        }
+
+        for &(attr, base, bright_base, _) in COLORS.iter() {
+            codes.extend(self._color_sgr(attr, base, bright_base));
+        }
+
+        codes.join(";")
+    }
+
+    // SGR code of the color set with the color variant of attr, if any.
+    fn _color_sgr(&self, attr: Attr, base: u32, bright_base: u32) -> Option<String> {
+        if let Some(Rgb(r, g, b)) = self._rgb(attr) {
+            Some(format!("{};2;{};{};{}", base + 8, r, g, b))
+        } else {
+            self._palette_color(attr).map(|color| match color {
+                0..=7 => format!("{}", base + color),
+                8..=15 => format!("{}", bright_base + color - 8),
+                _ => format!("{};5;{}", base + 8, color),
+            })
+        }
+    }
+
+    // SGR parameters turning on or off only the attrs that differ
+    // between prev and this style.
+    fn _sgr_diff(&self, prev: &Self) -> String {
+        let mut codes = Vec::with_capacity(8);
+
+        // 22 turns off both bold and dim, so the kept one is turned on again
+        let turned_off = |attr| prev.has_exact_attr(attr) && !self.has_exact_attr(attr);
+        let intensity_off = turned_off(Attr::Bold) || turned_off(Attr::Dim);
+        if intensity_off {
+            codes.push(String::from("22"));
+        }
+
+        for &(attr, code) in &[(Attr::Bold, 1), (Attr::Dim, 2)] {
+            if self.has_exact_attr(attr) && (intensity_off || !prev.has_exact_attr(attr)) {
+                codes.push(code.to_string());
+            }
+        }
+
+        // Italic(false), Underline(false) and Standout(false) look the
+        // same as not having the attrs set, so only the on states are
+        // compared. Standout is written as reverse.
+        let flags: [Flag; 5] = [
+            (|s| s.has_exact_attr(Attr::Italic(true)), 3, 23),
+            (|s| s.has_exact_attr(Attr::Underline(true)), 4, 24),
+            (|s| s.has_exact_attr(Attr::Blink), 5, 25),
+            (
+                |s| s.has_exact_attr(Attr::Reverse) || s.has_exact_attr(Attr::Standout(true)),
+                7,
+                27,
+            ),
+            (|s| s.has_exact_attr(Attr::Secure), 8, 28),
+        ];
+
+        for &(is_on, on_code, off_code) in flags.iter() {
+            match (is_on(prev), is_on(self)) {
+                (false, true) => codes.push(on_code.to_string()),
+                (true, false) => codes.push(off_code.to_string()),
+                _ => (),
+            }
+        }
+
+        for &(attr, base, bright_base, off_code) in COLORS.iter() {
+            let color = self._color_sgr(attr, base, bright_base);
+            if color != prev._color_sgr(attr, base, bright_base) {
+                codes.push(color.unwrap_or_else(|| off_code.to_string()));
+            }
+        }
+
+        codes.join(";")
+    }
+
+    // SGR parameters changing the rendition from prev to this style.
+    // Either only the changed attrs, or a reset followed by this style,
+    // whichever is shorter. Empty if nothing changes.
+    pub(crate) fn _sgr_from(&self, prev: &Self) -> String {
+        let diff = self._sgr_diff(prev);
+        let reset = match self.to_sgr() {
+            ref sgr if sgr.is_empty() => String::from("0"),
+            sgr => format!("0;{}", sgr),
+        };
+
+        if diff.len() <= reset.len() {
+            diff
+        } else {
+            reset
+        }
+    }
+
+    // Apply SGR parameters to style, as a terminal would.
+    // Unknown or malformed codes are skipped if strict=false.
+    pub(crate) fn apply_sgr(&mut self, sgr: &str, strict: bool) -> Result<()> {
+        let mut params = sgr.split(';').map(|p| match p {
+            "" => Some(0),
+            _ => p.parse::<u32>().ok(),
+        });
+
+        while let Some(param) = params.next() {
+            let code = match (param, strict) {
+                (Some(code), _) => code,
+                (None, true) => {
+                    return Err(Error::Parse(format!("invalid SGR parameters `{}`", sgr)));
+                },
+                (None, false) => continue,
+            };
+
+            match code {
+                0 => self.reset(),
+                1 => self.add_bold(),
+                2 => self.add_dim(),
+                3 => self.add_italic(true),
+                4 => self.add_underline(true),
+                5 | 6 => self.add_blink(),
+                7 => self.add_reverse(),
+                8 => self.add_secure(),
+                22 => {
+                    self.unset_bold();
+                    self.unset_dim();
+                },
+                23 => self.unset_italic(),
+                24 => self.unset_underline(),
+                25 => self.unset_blink(),
+                27 => {
+                    self.unset_reverse();
+                    self.unset_standout();
+                },
+                28 => self.unset_secure(),
+                30..=37 => self.add_fg(code - 30),
+                40..=47 => self.add_bg(code - 40),
+                90..=97 => self.add_fg(code - 90 + 8),
+                100..=107 => self.add_bg(code - 100 + 8),
+                39 => self.unset_fg(),
+                49 => self.unset_bg(),
+                38 | 48 => {
+                    let attr = match code {
+                        38 => Attr::ForegroundColor(0),
+                        _ => Attr::BackgroundColor(0),
+                    };
+
+                    match (extended_color(&mut params), attr) {
+                        (Some(SgrColor::Palette(c)), Attr::ForegroundColor(_)) => self.add_fg(c),
+                        (Some(SgrColor::Palette(c)), _) => self.add_bg(c),
+                        (Some(SgrColor::Rgb(rgb)), _) => self.add_rgb(attr, rgb),
+                        (None, _) if strict => {
+                            let e = format!("invalid extended color in SGR parameters `{}`", sgr);
+                            return Err(Error::Parse(e));
+                        },
+                        (None, _) => (),
+                    }
+                },
+                _ if strict => {
+                    let e = format!("unknown SGR code `{}` in `{}`", code, sgr);
+                    return Err(Error::Parse(e));
+                },
+                _ => (),
+            }
+        }
+
+        Ok(())
+    }
+}
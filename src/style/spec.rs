@@ -0,0 +1,271 @@
+/*
+    This file is a part of term-string.
+
+    Copyright (C) 2018 Mohammad AlSaleh <CE.Mohammad.AlSaleh at gmail.com>
+    https://github.com/rust-alt/term-string
+
+    This Source Code Form is subject to the terms of the Mozilla Public
+    License, v. 2.0. If a copy of the MPL was not distributed with this
+    file, You can obtain one at <http://mozilla.org/MPL/2.0/>.
+*/
+
+use std::fmt;
+use std::str::FromStr;
+
+use error::{Error, Result};
+
+use super::color::Color;
+use super::{Attr, Rgb, TermStyle};
+
+const COLOR_NAMES: [&str; 8] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+// Attrs with no data, and attrs with bool data, in canonical order.
+const FLAGS: [(&str, Attr); 5] = [
+    ("bold", Attr::Bold),
+    ("dim", Attr::Dim),
+    ("blink", Attr::Blink),
+    ("reverse", Attr::Reverse),
+    ("secure", Attr::Secure),
+];
+
+type BoolAttr = fn(bool) -> Attr;
+
+const BOOL_FLAGS: [(&str, BoolAttr); 3] = [
+    ("italic", Attr::Italic),
+    ("underline", Attr::Underline),
+    ("standout", Attr::Standout),
+];
+
+// Either a palette color, or an rgb one.
+enum SpecColor {
+    Palette(Color),
+    Rgb(Rgb),
+}
+
+fn parse_hex(hex: &str, spec: &str) -> Result<Rgb> {
+    let invalid = || Error::Parse(format!("invalid hex color `{}`", spec));
+
+    if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(invalid());
+    }
+
+    let digit = |i: usize, len: usize| u8::from_str_radix(&hex[i..i + len], 16);
+    let rgb = match hex.len() {
+        // #rgb is short for #rrggbb
+        3 => (digit(0, 1), digit(1, 1), digit(2, 1)),
+        6 => (digit(0, 2), digit(2, 2), digit(4, 2)),
+        _ => return Err(invalid()),
+    };
+
+    match rgb {
+        (Ok(r), Ok(g), Ok(b)) if hex.len() == 3 => Ok(Rgb(r * 0x11, g * 0x11, b * 0x11)),
+        (Ok(r), Ok(g), Ok(b)) => Ok(Rgb(r, g, b)),
+        _ => Err(invalid()),
+    }
+}
+
+fn parse_color(spec: &str) -> Result<SpecColor> {
+    if let Some(hex) = spec.strip_prefix('#') {
+        return parse_hex(hex, spec).map(SpecColor::Rgb);
+    }
+
+    if spec.is_empty() {
+        return Err(Error::Parse("expected a color".into()));
+    }
+
+    if spec.bytes().all(|b| b.is_ascii_digit()) {
+        return match spec.parse::<Color>() {
+            Ok(color) if color < 256 => Ok(SpecColor::Palette(color)),
+            _ => Err(Error::Parse(format!(
+                "color index `{}` out of range (0-255)",
+                spec
+            ))),
+        };
+    }
+
+    let (name, offset) = match spec
+        .strip_prefix("bright-")
+        .or_else(|| spec.strip_prefix("bright_"))
+    {
+        Some(name) => (name, 8),
+        None => (spec, 0),
+    };
+
+    COLOR_NAMES
+        .iter()
+        .position(|&n| n == name)
+        .map(|pos| SpecColor::Palette(pos as Color + offset))
+        .ok_or_else(|| Error::Parse(format!("unknown color `{}`", spec)))
+}
+
+fn add_color(style: &mut TermStyle, color: SpecColor, fg: bool) {
+    match (color, fg) {
+        (SpecColor::Palette(c), true) => style.add_fg(c),
+        (SpecColor::Palette(c), false) => style.add_bg(c),
+        (SpecColor::Rgb(Rgb(r, g, b)), true) => style.add_fg_rgb(r, g, b),
+        (SpecColor::Rgb(Rgb(r, g, b)), false) => style.add_bg_rgb(r, g, b),
+    }
+}
+
+/// Parse a [`TermStyle`] from a human-readable style spec.
+///
+/// A spec is a list of words separated by whitespace or commas.
+/// Words are case-insensitive, and can be:
+///
+/// * An attribute: `bold`, `dim`, `blink`, `reverse`, `secure`,
+///   `italic`, `underline` or `standout`.
+/// * `no-italic`, `no-underline` or `no-standout`, setting those attributes
+///   with `false`.
+/// * A color, setting the foreground color.
+/// * `on` followed by a color, setting the background color.
+/// * `fg=<color>` or `bg=<color>`.
+///
+/// A color is either a name (`black`, `red`, `green`, `yellow`, `blue`,
+/// `magenta`, `cyan` or `white`), optionally prefixed with `bright-`,
+/// a palette index (`0`-`255`), or an [`Rgb`] color in hex (`#ff8800`
+/// or `#f80`).
+///
+/// Later words override earlier ones. An empty spec is parsed
+/// into a default [`TermStyle`].
+///
+/// # Examples
+/// ``` rust
+/// use term_string::{color, TermStyle};
+///
+/// let style: TermStyle = "bold underline bright-red on blue".parse().unwrap();
+/// let expected = TermStyle::bold()
+///     + TermStyle::underline(true)
+///     + TermStyle::fg(color::BRIGHT_RED)
+///     + TermStyle::bg(color::BLUE);
+/// assert_eq!(style, expected);
+///
+/// let style: TermStyle = "fg=#ff0 bg=236 italic".parse().unwrap();
+/// let expected = TermStyle::fg_rgb(0xff, 0xff, 0x00)
+///     + TermStyle::bg(236)
+///     + TermStyle::italic(true);
+/// assert_eq!(style, expected);
+///
+/// assert!("bold on".parse::<TermStyle>().is_err());
+/// assert!("fg=256".parse::<TermStyle>().is_err());
+/// ```
+impl FromStr for TermStyle {
+    type Err = Error;
+
+    fn from_str(spec: &str) -> Result<Self> {
+        let spec = spec.to_lowercase();
+        let mut style = Self::default();
+        let mut words = spec
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|w| !w.is_empty());
+
+        while let Some(word) = words.next() {
+            if let Some(&(_, attr)) = FLAGS.iter().find(|&&(name, _)| name == word) {
+                style.add_attr(attr);
+            } else if let Some(&(_, attr)) = BOOL_FLAGS.iter().find(|&&(name, _)| name == word) {
+                style.add_attr(attr(true));
+            } else if let Some(&(_, attr)) = BOOL_FLAGS
+                .iter()
+                .find(|&&(name, _)| word.strip_prefix("no-") == Some(name))
+            {
+                style.add_attr(attr(false));
+            } else if word == "on" {
+                let color = words
+                    .next()
+                    .ok_or_else(|| Error::Parse("expected a color after `on`".into()))?;
+                add_color(&mut style, parse_color(color)?, false);
+            } else if let Some(color) = word.strip_prefix("fg=") {
+                add_color(&mut style, parse_color(color)?, true);
+            } else if let Some(color) = word.strip_prefix("bg=") {
+                add_color(&mut style, parse_color(color)?, false);
+            } else {
+                match parse_color(word) {
+                    Ok(color) => add_color(&mut style, color, true),
+                    Err(_) => {
+                        return Err(Error::Parse(format!("unknown style word `{}`", word)));
+                    },
+                }
+            }
+        }
+
+        Ok(style)
+    }
+}
+
+fn fmt_color(f: &mut fmt::Formatter, color: Color) -> fmt::Result {
+    match color {
+        0..=7 => write!(f, "{}", COLOR_NAMES[color as usize]),
+        8..=15 => write!(f, "bright-{}", COLOR_NAMES[color as usize - 8]),
+        _ => write!(f, "{}", color),
+    }
+}
+
+fn fmt_rgb(f: &mut fmt::Formatter, rgb: Rgb) -> fmt::Result {
+    let Rgb(r, g, b) = rgb;
+    write!(f, "#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+/// Format a [`TermStyle`] as a canonical style spec.
+///
+/// Attributes are written first in a fixed order, followed by the foreground
+/// color, then `on` and the background color. The output can be parsed back
+/// into an equal [`TermStyle`] (see [`FromStr`] above), except for palette
+/// colors out of the `0`-`255` range, which are written as their index but
+/// rejected when parsed.
+///
+/// # Examples
+/// ``` rust
+/// use term_string::{color, TermStyle};
+///
+/// let style = TermStyle::bg(color::BLUE)
+///     + TermStyle::fg(color::BRIGHT_RED)
+///     + TermStyle::underline(true)
+///     + TermStyle::bold();
+/// assert_eq!(style.to_string(), "bold underline bright-red on blue");
+///
+/// let style = TermStyle::italic(false) + TermStyle::bg_rgb(0xff, 0x88, 0x00);
+/// assert_eq!(style.to_string(), "no-italic on #ff8800");
+/// assert_eq!(style.to_string().parse::<TermStyle>().unwrap(), style);
+///
+/// assert_eq!(TermStyle::fg(300).to_string(), "300");
+/// assert!("300".parse::<TermStyle>().is_err());
+/// ```
+impl fmt::Display for TermStyle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut sep = "";
+
+        for &(name, attr) in FLAGS.iter() {
+            if self.has_exact_attr(attr) {
+                write!(f, "{}{}", sep, name)?;
+                sep = " ";
+            }
+        }
+
+        for &(name, attr) in BOOL_FLAGS.iter() {
+            if self.has_exact_attr(attr(true)) {
+                write!(f, "{}{}", sep, name)?;
+                sep = " ";
+            } else if self.has_exact_attr(attr(false)) {
+                write!(f, "{}no-{}", sep, name)?;
+                sep = " ";
+            }
+        }
+
+        let grounds = [("", Attr::ForegroundColor(0)), ("on ", Attr::BackgroundColor(0))];
+
+        for &(prefix, attr) in grounds.iter() {
+            if let Some(rgb) = self._rgb(attr) {
+                write!(f, "{}{}", sep, prefix)?;
+                fmt_rgb(f, rgb)?;
+                sep = " ";
+            } else if let Some(color) = self._palette_color(attr) {
+                write!(f, "{}{}", sep, prefix)?;
+                fmt_color(f, color)?;
+                sep = " ";
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -160,6 +160,31 @@ fn ops_partial_eq() {
     assert_eq!(style1, style4);
 }
 
+#[test]
+fn ops_eq_hash_ord() {
+    use std::collections::HashSet;
+    use style::color::BLUE;
+
+    let style1 = Sty::bold() | Sty::underline(false) | Sty::fg(RED);
+    let style2 = Sty::fg(RED) | Sty::underline(false) | Sty::bold();
+    let style3 = Sty::bold() | Sty::fg_rgb(1, 2, 3);
+    let style4 = Sty::bg(BLUE);
+
+    let set: HashSet<_> = [style1, style2, style3, style4, Sty::default()]
+        .iter()
+        .cloned()
+        .collect();
+    assert_eq!(set.len(), 4);
+    assert!(set.contains(&(Sty::fg(RED) | Sty::bold() | Sty::underline(false))));
+
+    let mut styles1 = vec![style4, style1, Sty::default(), style3];
+    let mut styles2 = vec![style3, style2, style4, Sty::default()];
+    styles1.sort();
+    styles2.sort();
+    assert_eq!(styles1, styles2);
+    assert_eq!(styles1[0], Sty::default());
+}
+
 #[test]
 fn ops_from() {
     let style1 = Sty::from([Attr::Bold, Attr::Underline(false), Attr::Secure]);
@@ -289,3 +314,306 @@ fn gen_attr_fns2() {
     style1.unset_exact_fg(RED);
     assert_eq!(style1, style2.without_exact_fg(RED));
 }
+
+#[test]
+fn const_fns() {
+    const BOLD_RED: Sty = Sty::new().with_bold().with_fg(RED);
+    const STYLES: [Sty; 3] = [
+        Sty::underline(true).with_italic(false),
+        Sty::bg_rgb(1, 2, 3).with_fg(RED).with_fg_rgb(4, 5, 6),
+        Sty::new()
+            .with_attr(Attr::Reverse)
+            .with_attr(Attr::BackgroundColor(RED)),
+    ];
+
+    assert_eq!(BOLD_RED, Sty::bold() | Sty::fg(RED));
+    assert_eq!(STYLES[0], Sty::underline(true) | Sty::italic(false));
+    assert_eq!(STYLES[1], Sty::bg_rgb(1, 2, 3) | Sty::fg_rgb(4, 5, 6));
+    assert_eq!(STYLES[2], Sty::reverse() | Sty::bg(RED));
+
+    // bool data replaces the old value
+    assert_eq!(Sty::italic(true).with_italic(false), Sty::italic(false));
+    assert_eq!(Sty::new(), Sty::default());
+}
+
+#[test]
+fn attr_order() {
+    let style1 = Sty::underline(false) | Sty::bold() | Sty::fg(RED);
+    let style2 = Sty::fg(RED) | Sty::bold() | Sty::underline(false);
+    assert_eq!(format!("{:?}", style1), format!("{:?}", style2));
+
+    let mut style3 = Sty::bold() | Sty::dim() | Sty::underline(false);
+    style3.unset_dim();
+    style3.add_fg(RED);
+    assert_eq!(format!("{:?}", style1), format!("{:?}", style3));
+}
+
+#[test]
+fn gen_rgb_fns() {
+    let mut style1 = Sty::fg_rgb(0xff, 0x88, 0x00) | Sty::bold();
+    let style2 = Sty::bold().with_fg_rgb(0xff, 0x88, 0x00);
+    assert_eq!(style1, style2);
+    assert!(style1.has_fg());
+    assert!(style1.has_fg_rgb());
+    assert!(style1.has_exact_fg_rgb(0xff, 0x88, 0x00));
+    assert!(!style1.has_exact_fg_rgb(0xff, 0x88, 0x01));
+    assert!(!style1.has_bg_rgb());
+
+    // rgb and palette colors share the same variant
+    style1.or_fg(RED);
+    assert_eq!(style1, style2);
+    style1.add_fg(RED);
+    assert!(!style1.has_fg_rgb());
+    assert_eq!(style1, Sty::bold() | Sty::fg(RED));
+    style1.or_fg_rgb(0xff, 0x88, 0x00);
+    assert_eq!(style1, Sty::bold() | Sty::fg(RED));
+    style1.add_fg_rgb(0xff, 0x88, 0x00);
+    assert_eq!(style1, style2);
+
+    style1.unset_exact_fg_rgb(0xff, 0x88, 0x01);
+    assert_eq!(style1, style2);
+    style1.unset_fg();
+    assert_eq!(style1, Sty::bold());
+    assert_eq!(style2.without_fg_rgb(), Sty::bold());
+}
+
+#[test]
+fn rgb_style_ops() {
+    let style1 = Sty::bold() | Sty::bg_rgb(0, 0, 0x80);
+    let style2 = Sty::bg(RED) | Sty::underline(true);
+
+    assert!(style1.has_variant_style(Sty::bg(RED)));
+    assert!(!style1.has_exact_style(Sty::bg(RED)));
+    assert!(style1.has_exact_style(Sty::bg_rgb(0, 0, 0x80)));
+
+    let style3 = Sty::bold() | Sty::underline(true) | Sty::bg_rgb(0, 0, 0x80);
+    assert_eq!(style1 | style2, style3);
+    assert_eq!(style1 + style2, Sty::bold() | Sty::underline(true) | Sty::bg(RED));
+    assert_eq!(style2 + style1, style3);
+    assert_eq!(style1 - Sty::bg_rgb(0, 0, 0x81), style1);
+    assert_eq!(style1 - Sty::bg_rgb(0, 0, 0x80), Sty::bold());
+    assert_eq!(style1.without_variant_style(Sty::bg(RED)), Sty::bold());
+    assert_ne!(style1, Sty::bold() | Sty::bg_rgb(0, 0, 0x81));
+}
+
+#[test]
+fn downsample() {
+    use style::color::{BRIGHT_RED, WHITE};
+    use style::ColorDepth;
+
+    let style1 = Sty::bold() | Sty::fg_rgb(0xff, 0x00, 0x00) | Sty::bg(WHITE);
+    assert_eq!(style1.with_downsampled(ColorDepth::TrueColor), style1);
+    assert_eq!(
+        style1.with_downsampled(ColorDepth::Ansi256),
+        Sty::bold() | Sty::fg(196) | Sty::bg(WHITE)
+    );
+    assert_eq!(
+        style1.with_downsampled(ColorDepth::Ansi16),
+        Sty::bold() | Sty::fg(BRIGHT_RED) | Sty::bg(WHITE)
+    );
+    assert_eq!(
+        style1.with_downsampled(ColorDepth::Ansi8),
+        Sty::bold() | Sty::fg(RED) | Sty::bg(WHITE)
+    );
+    assert_eq!(style1.with_downsampled(ColorDepth::NoColor), Sty::bold());
+
+    // grays map to the grayscale ramp, then to black/white variants
+    let style2 = Sty::bg_rgb(0x30, 0x30, 0x30);
+    assert_eq!(style2.with_downsampled(ColorDepth::Ansi256), Sty::bg(236));
+    assert_eq!(Sty::bg(236).with_downsampled(ColorDepth::Ansi16), Sty::bg(0));
+    assert_eq!(Sty::bg(250).with_downsampled(ColorDepth::Ansi16), Sty::bg(7));
+}
+
+#[test]
+fn from_str() {
+    use style::color::{BLUE, BRIGHT_RED};
+
+    let style1 = Sty::bold() | Sty::underline(true) | Sty::fg(BRIGHT_RED) | Sty::bg(BLUE);
+    assert_eq!("bold underline bright-red on blue".parse::<Sty>().unwrap(), style1);
+    assert_eq!("Bold, Underline, fg=9, bg=4".parse::<Sty>().unwrap(), style1);
+    assert_eq!("  ".parse::<Sty>().unwrap(), Sty::default());
+
+    let style2 = Sty::italic(false) | Sty::fg_rgb(0xff, 0x88, 0x00) | Sty::bg(236);
+    assert_eq!("no-italic #ff8800 on 236".parse::<Sty>().unwrap(), style2);
+    assert_eq!("red no-italic fg=#f80 bg=236".parse::<Sty>().unwrap(), style2);
+
+    assert!("bold on".parse::<Sty>().is_err());
+    assert!("bold purple".parse::<Sty>().is_err());
+    assert!("bg=bright-purple".parse::<Sty>().is_err());
+    assert!("fg=#ff88".parse::<Sty>().is_err());
+    assert!("fg=#gg8800".parse::<Sty>().is_err());
+    assert!("256".parse::<Sty>().is_err());
+    let err = "fg=".parse::<Sty>().unwrap_err();
+    assert_eq!(err.to_string(), "Parse Error: expected a color");
+    assert!("bold bg=".parse::<Sty>().is_err());
+}
+
+#[test]
+fn display() {
+    let style1 = Sty::bg(RED) | Sty::standout(true) | Sty::bold() | Sty::fg(100);
+    assert_eq!(style1.to_string(), "bold standout 100 on red");
+    assert_eq!(style1.to_string().parse::<Sty>().unwrap(), style1);
+
+    let style2 = Sty::underline(false) | Sty::bg_rgb(0x00, 0x0a, 0xff);
+    assert_eq!(style2.to_string(), "no-underline on #000aff");
+    assert_eq!(style2.to_string().parse::<Sty>().unwrap(), style2);
+
+    assert_eq!(Sty::default().to_string(), "");
+}
+
+#[test]
+fn from_sgr() {
+    use style::color::{BLUE, BRIGHT_GREEN};
+
+    let style1 = Sty::bold() | Sty::fg(RED) | Sty::bg(BLUE);
+    assert_eq!(Sty::from_sgr("1;31;44").unwrap(), style1);
+    assert_eq!(Sty::from_sgr("01;31;44").unwrap(), style1);
+    assert_eq!(Sty::from_sgr("4;0;1;31;44").unwrap(), style1);
+    assert_eq!(Sty::from_sgr("1;31;44;22;39").unwrap(), Sty::bg(BLUE));
+    assert_eq!(Sty::from_sgr("").unwrap(), Sty::default());
+
+    let style2 = Sty::dim() | Sty::fg(BRIGHT_GREEN) | Sty::bg_rgb(1, 2, 3);
+    assert_eq!(Sty::from_sgr("2;92;48;2;1;2;3;23").unwrap(), style2);
+    assert_eq!(Sty::from_sgr("2;38;5;10;3;48;2;1;2;3;23").unwrap(), style2);
+
+    // Off codes unset attrs
+    assert_eq!(Sty::from_sgr("3;4;23;24").unwrap(), Sty::default());
+    assert_eq!(Sty::from_sgr("4;24;4").unwrap(), Sty::underline(true));
+    assert_eq!(Sty::from_sgr(&Sty::italic(false).to_sgr()).unwrap(), Sty::default());
+
+    assert!(Sty::from_sgr("1;x").is_err());
+    assert!(Sty::from_sgr("1;99").is_err());
+    assert!(Sty::from_sgr("38;2;1;2").is_err());
+    assert!(Sty::from_sgr("48;5;256").is_err());
+    assert_eq!(Sty::from_sgr_lossy("1;99;31;44;48;5"), style1);
+}
+
+#[test]
+fn to_sgr() {
+    let style1 = Sty::fg(RED) | Sty::underline(true) | Sty::bold() | Sty::bg(244);
+    assert_eq!(style1.to_sgr(), "1;4;31;48;5;244");
+    assert_eq!(Sty::from_sgr(&style1.to_sgr()).unwrap(), style1);
+
+    let style2 = Sty::reverse() | Sty::standout(true) | Sty::fg_rgb(0xff, 0x88, 0);
+    assert_eq!(style2.to_sgr(), "7;38;2;255;136;0");
+
+    assert_eq!(Sty::default().to_sgr(), "");
+}
+
+#[test]
+fn sgr_from() {
+    let style1 = Sty::bold() | Sty::underline(true) | Sty::fg(RED);
+    let style2 = Sty::dim() | Sty::standout(true) | Sty::fg_rgb(1, 2, 3) | Sty::bg(244);
+
+    assert_eq!(style1._sgr_from(&Sty::default()), "1;4;31");
+    assert_eq!(style1._sgr_from(&style1), "");
+    assert_eq!(style1._sgr_from(&(style1 | Sty::reverse())), "27");
+    assert_eq!(((style1 - Sty::bold()) | Sty::dim())._sgr_from(&style1), "22;2");
+    assert_eq!(Sty::default()._sgr_from(&style2), "0");
+
+    // Reset and re-write if shorter
+    assert_eq!(Sty::bold()._sgr_from(&style2), "0;1");
+    assert_eq!(style2._sgr_from(&style1), "0;2;7;38;2;1;2;3;48;5;244");
+
+    // Standout is written as reverse
+    assert_eq!(Sty::reverse()._sgr_from(&Sty::standout(true)), "");
+    assert_eq!(Sty::standout(false)._sgr_from(&Sty::standout(true)), "27");
+}
+
+#[test]
+fn iter() {
+    let style = Sty::bg(RED) | Sty::standout(false) | Sty::fg_rgb(1, 2, 3) | Sty::bold();
+    let attrs: Vec<_> = style.into_iter().collect();
+    assert_eq!(attrs, [Attr::Bold, Attr::Standout(false), Attr::BackgroundColor(RED)]);
+    // len() counts the rgb color iter() skips
+    assert_eq!((&style).into_iter().count(), 3);
+    assert_eq!(style.len(), 4);
+    assert!(!style.is_empty());
+
+    // Round-trip, without rgb colors
+    assert_eq!(style.iter().collect::<Sty>(), style.without_fg_rgb());
+
+    let mut style2: Sty = vec![Attr::Underline(true), Attr::Underline(false)]
+        .into_iter()
+        .collect();
+    assert_eq!(style2, Sty::underline(false));
+    style2.extend(Sty::bold().iter());
+    assert_eq!(style2, Sty::underline(false) | Sty::bold());
+
+    assert_eq!(Sty::default().iter().next(), None);
+    assert_eq!(Sty::default().len(), 0);
+    assert!(Sty::default().is_empty());
+}
+
+#[test]
+fn set_ops() {
+    use style::color::BLUE;
+
+    let style1 = Sty::bold() | Sty::underline(true) | Sty::fg(RED) | Sty::bg_rgb(1, 2, 3);
+    let style2 =
+        Sty::bold() | Sty::underline(false) | Sty::fg(BLUE) | Sty::bg_rgb(1, 2, 3) | Sty::dim();
+
+    assert_eq!(style1 & style2, Sty::bold() | Sty::bg_rgb(1, 2, 3));
+    assert_eq!(style1.variant_intersection(style2), style1);
+    assert_eq!(style2.variant_intersection(style1), style2 - Sty::dim());
+
+    assert_eq!(style1.difference(style2), Sty::underline(true) | Sty::fg(RED));
+    assert_eq!(style1.variant_difference(style2), Sty::default());
+    assert_eq!(style2.variant_difference(style1), Sty::dim());
+
+    assert_eq!(style1 ^ style2, Sty::underline(true) | Sty::fg(RED) | Sty::dim());
+    assert_eq!(style2 ^ style1, Sty::underline(false) | Sty::fg(BLUE) | Sty::dim());
+    assert_eq!(style1.variant_symmetric_difference(style2), Sty::dim());
+    assert_eq!(style1 ^ style1, Sty::default());
+
+    let mut style3 = style1;
+    style3 &= Sty::underline(true) | Sty::fg(BLUE);
+    assert_eq!(style3, Sty::underline(true));
+    style3 ^= Sty::underline(true) | Sty::dim();
+    assert_eq!(style3, Sty::dim());
+
+    assert!((style1 & style2).is_subset_of(style1));
+    assert!(!style1.is_subset_of(style2));
+    assert!((style1 - Sty::bg_rgb(1, 2, 3)).is_variant_subset_of(style2));
+    assert!(!style2.is_variant_subset_of(style1));
+    assert!(Sty::default().is_subset_of(Sty::default()));
+}
+
+#[test]
+fn depth_from_terminfo() {
+    use std::collections::HashMap;
+    use style::ColorDepth;
+    use term::terminfo::TermInfo;
+
+    let ti = |colors: Option<u32>| TermInfo {
+        names: vec!["test".into()],
+        bools: HashMap::new(),
+        numbers: colors.into_iter().map(|n| ("colors", n)).collect(),
+        strings: HashMap::new(),
+    };
+    let depth = |colors, colorterm| ColorDepth::_from_terminfo(Some(&ti(colors)), colorterm);
+
+    assert_eq!(depth(None, None), ColorDepth::NoColor);
+    assert_eq!(depth(Some(2), None), ColorDepth::NoColor);
+    assert_eq!(depth(Some(8), None), ColorDepth::Ansi8);
+    assert_eq!(depth(Some(16), None), ColorDepth::Ansi16);
+    assert_eq!(depth(Some(88), None), ColorDepth::Ansi16);
+    assert_eq!(depth(Some(256), None), ColorDepth::Ansi256);
+    assert_eq!(depth(Some(0x100_0000), None), ColorDepth::Ansi256);
+
+    // COLORTERM wins over colors
+    for &colorterm in &["truecolor", "24bit"] {
+        for &colors in &[None, Some(8), Some(256)] {
+            assert_eq!(depth(colors, Some(colorterm)), ColorDepth::TrueColor);
+        }
+    }
+
+    // Other values are ignored
+    for &colorterm in &["", "yes", "TRUECOLOR", "256color"] {
+        assert_eq!(depth(Some(256), Some(colorterm)), ColorDepth::Ansi256);
+    }
+
+    // No terminfo, as in from_env() with an unknown TERM
+    assert_eq!(ColorDepth::_from_terminfo(None, None), ColorDepth::NoColor);
+    assert_eq!(ColorDepth::_from_terminfo(None, Some("truecolor")), ColorDepth::NoColor);
+}